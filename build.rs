@@ -1,9 +1,24 @@
-use std::{env, fs, process};
+use std::{
+    env, fs,
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The directory packed into the initramfs unless `INITRAMFS_DIR` says otherwise
+const DEFAULT_INITRAMFS_DIR: &str = "initramfs";
+
+const USTAR_BLOCK_SIZE: usize = 512;
 
 fn main() {
+    rerun_if_linker_scripts_changed();
+    build_initramfs().unwrap();
+}
+
+fn rerun_if_linker_scripts_changed() {
     let ld_script_path = match env::var("LD_SCRIPT_PATH") {
         Ok(var) => var,
-        _ => process::exit(0),
+        _ => return,
     };
 
     let files = fs::read_dir(ld_script_path).unwrap();
@@ -18,3 +33,90 @@ fn main() {
         })
         .for_each(|f| println!("cargo:rerun-if-changed={}", f.path().display()));
 }
+
+/// Pack the initramfs directory into `$OUT_DIR/initramfs.tar` for the kernel to embed
+///
+/// A missing directory gives an empty archive
+fn build_initramfs() -> io::Result<()> {
+    println!("cargo:rerun-if-env-changed=INITRAMFS_DIR");
+
+    let dir = env::var("INITRAMFS_DIR").unwrap_or_else(|_| DEFAULT_INITRAMFS_DIR.into());
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("initramfs.tar");
+
+    println!("cargo:rerun-if-changed={}", dir);
+
+    let mut entries = Vec::new();
+    let dir = Path::new(&dir);
+
+    if dir.is_dir() {
+        collect(dir, dir, &mut entries)?;
+    }
+
+    // Sorted, so the image only changes when the files do
+    entries.sort();
+
+    let mut archive = File::create(out)?;
+
+    for (name, path) in entries {
+        append(&mut archive, &name, &path)?;
+    }
+
+    // The end of the archive is marked by two zero blocks
+    archive.write_all(&[0; 2 * USTAR_BLOCK_SIZE])
+}
+
+/// Every file and directory below `dir` with its name in the archive
+fn collect(root: &Path, dir: &Path, entries: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .strip_prefix(root)
+            .unwrap()
+            .to_str()
+            .ok_or_else(|| io::Error::other(format!("{} is not UTF-8", path.display())))?
+            .replace('\\', "/");
+
+        if path.is_dir() {
+            entries.push((format!("{}/", name), path.clone()));
+            collect(root, &path, entries)?;
+        } else if path.is_file() {
+            entries.push((name, path));
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the ustar header and the contents of `path`
+fn append(archive: &mut File, name: &str, path: &Path) -> io::Result<()> {
+    let is_dir = name.ends_with('/');
+    let data = if is_dir { Vec::new() } else { fs::read(path)? };
+
+    if name.len() > 100 {
+        return Err(io::Error::other(format!("{}: name longer than 100 bytes", name)));
+    }
+
+    let mut header = [0u8; USTAR_BLOCK_SIZE];
+
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..107].copy_from_slice(if is_dir { b"0000755" } else { b"0000644" });
+    header[108..115].copy_from_slice(b"0000000");
+    header[116..123].copy_from_slice(b"0000000");
+    header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+    header[136..147].copy_from_slice(b"00000000000");
+    header[156] = if is_dir { b'5' } else { b'0' };
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // The checksum is calculated with its own field set to spaces
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..155].copy_from_slice(format!("{:06o}\0", checksum).as_bytes());
+
+    archive.write_all(&header)?;
+    archive.write_all(&data)?;
+
+    let padding = data.len().next_multiple_of(USTAR_BLOCK_SIZE) - data.len();
+
+    archive.write_all(&vec![0; padding])
+}
@@ -2,11 +2,57 @@
 //
 // Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
 
-use aarch64_cpu::asm;
+use core::arch::asm;
+
+use aarch64_cpu::asm::{
+  self,
+  barrier,
+};
 
 pub use asm::nop;
 
+/// Data cache line size; the same on the Cortex-A53 and Cortex-A72
+const CACHE_LINE_SIZE: usize = 64;
+
 #[inline(always)]
 pub fn wait_forever() -> ! {
   loop { asm::wfe() }
-}
\ No newline at end of file
+}
+
+/// Sleep until an interrupt is pending; it is taken if IRQs are unmasked
+#[inline(always)]
+pub fn wait_for_interrupt() {
+  asm::wfi()
+}
+
+/// Clean and invalidate the data cache lines covering `len` bytes from `start`; up to the point of coherency
+///
+/// Needed for memory shared with bus masters that don't snoop the caches; e.g. the VideoCore
+pub fn clean_invalidate_dcache_range(start: usize, len: usize) {
+  let end = start + len;
+  let mut line = start & !(CACHE_LINE_SIZE - 1);
+
+  while line < end {
+    unsafe { asm!("dc civac, {}", in(reg) line, options(nostack, preserves_flags)) };
+
+    line += CACHE_LINE_SIZE;
+  }
+
+  barrier::dsb(barrier::SY);
+}
+
+/// Invalidate the data cache lines covering `len` bytes from `start`, dropping dirty data; up to the point of coherency
+///
+/// For reading what a bus master wrote; anything else sharing the lines is lost, so the range should be whole cache lines
+pub fn invalidate_dcache_range(start: usize, len: usize) {
+  let end = start + len;
+  let mut line = start & !(CACHE_LINE_SIZE - 1);
+
+  while line < end {
+    unsafe { asm!("dc ivac, {}", in(reg) line, options(nostack, preserves_flags)) };
+
+    line += CACHE_LINE_SIZE;
+  }
+
+  barrier::dsb(barrier::SY);
+}
@@ -31,7 +31,14 @@ use tock_registers::{
   registers::InMemoryRegister,
 };
 
-use crate::exception::PrivilegeLevel;
+use crate::{
+  exception::{
+    self,
+    PrivilegeLevel,
+  },
+  symbols::Symbolized,
+  thread,
+};
 
 global_asm!(include_str!("exception.s"));
 
@@ -156,6 +163,8 @@ impl Display for ExceptionContext {
 
     writeln!(f, "{}", self.spsr_el1)?;
     writeln!(f)?;
+    writeln!(f, "ELR_EL1: {}", Symbolized(self.elr_el1 as usize))?;
+    writeln!(f)?;
     writeln!(f, "General Purpose Registers:")?;
 
     let alternating = |x| -> &'static str {
@@ -167,7 +176,7 @@ impl Display for ExceptionContext {
       write!(f, "x{: <2}: {: >#018x}{}", i, reg, alternating(i))?;
     }
 
-    write!(f, "lr: {:#018x}", self.lr)
+    write!(f, "lr: {}", Symbolized(self.lr as usize))
   }
 }
 
@@ -209,8 +218,11 @@ extern "C" fn current_elx_synchronous(e: &mut ExceptionContext) {
 }
 
 #[unsafe(no_mangle)]
-extern "C" fn current_elx_irq(e: &mut ExceptionContext) {
-  default_exception_handler(e);
+extern "C" fn current_elx_irq(_e: &mut ExceptionContext) {
+  exception::asynchronous::irq_manager().handle_pending_irqs();
+
+  // Only once the interrupt controller is done; the interrupted thread might not continue for a while
+  thread::preempt();
 }
 
 #[unsafe(no_mangle)]
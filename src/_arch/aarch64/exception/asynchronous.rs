@@ -9,8 +9,20 @@
 //! Since modules are imported into generic modules using the path attribute,
 //! the path of this file is: `crate::exception::asynchronous::arch_asynchronous`
 
+use core::{
+  arch::asm,
+  fmt,
+};
+
 use aarch64_cpu::registers::*;
-use tock_registers::interfaces::Readable;
+use tock_registers::interfaces::{
+  Readable,
+  Writeable,
+};
+
+mod daif_bits {
+  pub const IRQ: u8 = 0b0010;
+}
 
 trait DaifField {
   fn daif_field() -> tock_registers::fields::Field<u64, DAIF::Register>;
@@ -42,14 +54,78 @@ where T: DaifField {
   DAIF.is_set(T::daif_field())
 }
 
+/// Unmask IRQs on the executing core
+///
+/// It is not needed to place an explicit instruction synchronization barrier after the `msr`
+/// Quoting the Architecture Reference Manual for ARMv8-A, section C5.1.3:
+///
+/// "Writes to PSTATE.{PAN, D, A, I, F} occur in program order without the need for additional synchronization."
+#[inline(always)]
+pub fn local_irq_unmask() {
+  unsafe {
+    asm!(
+      "msr DAIFClr, {arg}",
+      arg = const daif_bits::IRQ,
+      options(nomem, nostack, preserves_flags)
+    );
+  }
+}
+
+/// Mask IRQs on the executing core
+#[inline(always)]
+pub fn local_irq_mask() {
+  unsafe {
+    asm!(
+      "msr DAIFSet, {arg}",
+      arg = const daif_bits::IRQ,
+      options(nomem, nostack, preserves_flags)
+    );
+  }
+}
+
+/// Mask IRQs on the executing core and return the previously saved interrupt mask bits (DAIF)
+#[inline(always)]
+pub fn local_irq_mask_save() -> u64 {
+  let saved = DAIF.get();
+  local_irq_mask();
+
+  saved
+}
+
+/// Restore the interrupt mask bits (DAIF) using the callee's argument
+///
+/// # Invariant
+///
+/// - No sanity checks on the input
+#[inline(always)]
+pub fn local_irq_restore(saved: u64) {
+  DAIF.set(saved);
+}
+
+/// The mask state of each exception type
+fn mask_states() -> [(&'static str, bool); 4] {
+  [
+    ("Debug:",  is_masked::<Debug>()),
+    ("SError:", is_masked::<SError>()),
+    ("IRQ:",    is_masked::<IRQ>()),
+    ("FIQ:",    is_masked::<FIQ>()),
+  ]
+}
+
+fn to_mask_str(masked: bool) -> &'static str {
+  if masked { "Masked" } else { "Unmasked" }
+}
+
 /// Print the AArch64 exception status
 pub fn print_state() {
   use crate::info;
 
-  let to_mask_str = |s| -> _ { if s { "Masked"} else { "Unmasked"} };
+  for (name, masked) in mask_states() { info!("\t{:<7} {}", name, to_mask_str(masked)); }
+}
+
+/// Write the AArch64 exception status to `w`; one exception type per line
+pub fn write_state(w: &mut dyn fmt::Write) -> fmt::Result {
+  for (name, masked) in mask_states() { writeln!(w, "{:<7} {}", name, to_mask_str(masked))?; }
 
-  info!("\tDebug:  {}", to_mask_str(is_masked::<Debug>()));
-  info!("\tSError: {}", to_mask_str(is_masked::<SError>()));
-  info!("\tIRQ:    {}", to_mask_str(is_masked::<IRQ>()));
-  info!("\tFIQ:    {}", to_mask_str(is_masked::<FIQ>()));
-}
\ No newline at end of file
+  Ok(())
+}
@@ -42,6 +42,7 @@ pub type Granule64KiB  = TranslationGranule<{  64 * 1024        }>;
 pub mod mair {
   pub const DEVICE: u64 = 0;
   pub const NORMAL: u64 = 1;
+  pub const NORMAL_NON_CACHEABLE: u64 = 2;
 }
 
 /// The kernel translation tables
@@ -69,6 +70,11 @@ impl MemoryManagementUnit {
   fn set_up_mair(&self) {
     // Define the memory types being mapped
     MAIR_EL1.write(
+      // Attribute 2: Non-cacheable normal DRAM
+      MAIR_EL1::Attr2_Normal_Outer::NonCacheable
+      +
+      MAIR_EL1::Attr2_Normal_Inner::NonCacheable
+      +
       // Attribute 1: Cacheable normal DRAM
       MAIR_EL1::Attr1_Normal_Outer::WriteBack_NonTransient_ReadWriteAlloc
       +
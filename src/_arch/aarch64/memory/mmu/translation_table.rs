@@ -186,6 +186,11 @@ impl convert::From<AttributeFields> for tock_registers::fields::FieldValue<u64,
         +
         STAGE1_PAGE_DESCRIPTOR::AttrIndex.val(mair::NORMAL)
       }
+      MemAttributes::NonCacheableDRAM => {
+        STAGE1_PAGE_DESCRIPTOR::SH::OuterShareable
+        +
+        STAGE1_PAGE_DESCRIPTOR::AttrIndex.val(mair::NORMAL_NON_CACHEABLE)
+      }
       MemAttributes::Device => {
         STAGE1_PAGE_DESCRIPTOR::SH::OuterShareable
         +
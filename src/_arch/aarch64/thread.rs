@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Architectural thread context switching
+//!
+//! # Overview
+//!
+//! Since arch modules are imported into generic modules using the path attribute,
+//! the path of this file is: `crate::thread::arch_thread`
+
+use core::arch::global_asm;
+
+global_asm!(include_str!("thread.s"));
+
+/// The registers a thread keeps across a switch
+///
+/// Only the callee-saved registers (x19 to x28), the frame pointer, the stack pointer and the link register are needed
+/// The compiler saves the others around the call to `context_switch`; those of an interrupted thread are on its stack since exception entry
+#[repr(C)]
+pub struct Context {
+  callee_saved: [u64; 10],
+  fp: u64,
+  sp: u64,
+  lr: u64,
+}
+
+unsafe extern "C" {
+  fn __context_switch(prev: *mut Context, next: *const Context);
+}
+
+impl Context {
+  /// Create an instance; filled in on the first switch away from the thread
+  pub const fn new() -> Self {
+    Self {
+      callee_saved: [0; 10],
+      fp: 0,
+      sp: 0,
+      lr: 0,
+    }
+  }
+
+  /// The context of a thread that starts at `entry`, on the stack ending at `stack_top`
+  pub fn new_thread(stack_top: usize, entry: extern "C" fn() -> !) -> Self {
+    Self {
+      callee_saved: [0; 10],
+      // Ends the chain of frame records
+      fp: 0,
+      sp: stack_top as u64,
+      lr: entry as usize as u64,
+    }
+  }
+}
+
+/// Save the executing thread's registers into `prev` and continue the thread in `next`
+///
+/// Returns once another switch continues `prev`
+///
+/// # Safety
+///
+/// - `next` must have been saved by an earlier switch or created with `Context::new_thread`
+/// - IRQs must be masked on the executing core
+pub unsafe fn context_switch(prev: *mut Context, next: *const Context) {
+  unsafe { __context_switch(prev, next) }
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+.section .text
+
+// ------------------------------------------------------------ //
+// fn __context_switch(prev: *mut Context, next: *const Context) //
+// ------------------------------------------------------------ //
+.global __context_switch
+__context_switch:
+  // Save the callee-saved registers, the frame pointer, the stack pointer and the return address into `prev`
+  mov x9, sp
+
+  stp x19, x20, [x0, #16 * 0]
+  stp x21, x22, [x0, #16 * 1]
+  stp x23, x24, [x0, #16 * 2]
+  stp x25, x26, [x0, #16 * 3]
+  stp x27, x28, [x0, #16 * 4]
+  stp x29,  x9, [x0, #16 * 5]
+  str  lr,      [x0, #16 * 6]
+
+  // Load the ones of `next`
+  ldp x19, x20, [x1, #16 * 0]
+  ldp x21, x22, [x1, #16 * 1]
+  ldp x23, x24, [x1, #16 * 2]
+  ldp x25, x26, [x1, #16 * 3]
+  ldp x27, x28, [x1, #16 * 4]
+  ldp x29,  x9, [x1, #16 * 5]
+  ldr  lr,      [x1, #16 * 6]
+
+  mov sp, x9
+
+  // Continue where `next` called this; or at its entry point if it is a new thread
+  ret
+
+.size __context_switch, . - __context_switch
+.type __context_switch, function
@@ -26,7 +26,10 @@ use aarch64_cpu::{
   asm::barrier,
   registers::*,
 };
-use tock_registers::interfaces::Readable;
+use tock_registers::interfaces::{
+  Readable,
+  Writeable,
+};
 
 use crate::warn;
 
@@ -141,3 +144,19 @@ pub fn spin_for(duration: Duration) {
   // Read CNTPCT_EL0 directly to avoid the ISB that is part of [`read_cntpct`]
   while GenericTimerCounterValue(CNTPCT_EL0.get()) < counter_value_target {}
 }
+
+/// Raise the timer IRQ once `duration` has passed; replacing the pending timeout, if any
+///
+/// Setting a new timeout also clears a raised IRQ
+pub fn set_timeout_irq(duration: Duration) {
+  let counter_value_delta: GenericTimerCounterValue = match duration.try_into() {
+    Err(msg) => {
+      warn!("set_timeout_irq: {} - skipping", msg);
+      return;
+    }
+    Ok(val) => val,
+  };
+
+  CNTP_TVAL_EL0.set(counter_value_delta.0);
+  CNTP_CTL_EL0.write(CNTP_CTL_EL0::ENABLE::SET + CNTP_CTL_EL0::IMASK::CLEAR);
+}
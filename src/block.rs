@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Block devices
+//!
+//! Drivers register their disks with `add_disk` under a name; e.g. `sd0`
+//! The disk's MBR or GPT partitions are then registered as block devices of their own; e.g. `sd0p1`
+//!
+//! Only 512-byte blocks are supported
+
+mod cache;
+mod commands;
+mod partition;
+mod ram_disk;
+
+use crate::{
+  common::FixedString,
+  info,
+  shell,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  warn,
+};
+
+pub use cache::BlockCache;
+pub use ram_disk::RamDisk;
+
+/// The size of a block in bytes
+pub const BLOCK_SIZE: usize = 512;
+
+const NUM_BLOCK_DEVICES: usize = 16;
+
+/// The longest block device name
+const MAX_NAME_LEN: usize = 15;
+
+pub mod interface {
+  /// Block device functions
+  ///
+  /// Buffers must be a multiple of the block size long
+  pub trait BlockDevice {
+    /// The size of a block in bytes
+    fn block_size(&self) -> usize;
+
+    /// The number of blocks
+    fn num_blocks(&self) -> u64;
+
+    /// Read the blocks starting at `lba` into `buf`
+    fn read_blocks(&self, lba: u64, buf: &mut [u8]) -> Result<(), &'static str>;
+
+    /// Write `buf` to the blocks starting at `lba`
+    fn write_blocks(&self, lba: u64, buf: &[u8]) -> Result<(), &'static str>;
+  }
+}
+
+/// A block device name; stored inline, so partition names can be made up at runtime
+pub type Name = FixedString<MAX_NAME_LEN>;
+
+/// Describes a block device
+#[derive(Copy, Clone)]
+pub struct BlockDeviceDescriptor {
+  name: Name,
+  device: &'static (dyn interface::BlockDevice + Sync),
+}
+
+struct BlockManagerInner {
+  descriptors: [Option<BlockDeviceDescriptor>; NUM_BLOCK_DEVICES],
+}
+
+/// Manages all block devices
+pub struct BlockManager {
+  inner: NullLock<BlockManagerInner>,
+}
+
+unsafe impl Sync for BlockManager {}
+
+static BLOCK_MANAGER: BlockManager = BlockManager::new();
+
+/// A RAM disk that is always there; for trying out partition tables and filesystems without a card
+static RAM_DISK: RamDisk<RAM_DISK_BLOCKS> = RamDisk::new();
+
+/// 64 KiB
+const RAM_DISK_BLOCKS: usize = 128;
+
+impl BlockDeviceDescriptor {
+  /// Create an instance
+  pub fn new(name: &str, device: &'static (dyn interface::BlockDevice + Sync)) -> Result<Self, &'static str> {
+    Ok(Self { name: Name::try_from_str(name).map_err(|_| "Block device name too long")?, device })
+  }
+
+  /// The name the device is registered under
+  pub fn name(&self) -> &str {
+    self.name.as_str()
+  }
+
+  /// The device
+  #[allow(dead_code)]
+  pub const fn device(&self) -> &'static (dyn interface::BlockDevice + Sync) {
+    self.device
+  }
+}
+
+/// Check that `len` bytes are a whole number of blocks and that they fit on a device of `num_blocks` starting at `lba`
+pub fn check_range(lba: u64, len: usize, num_blocks: u64) -> Result<(), &'static str> {
+  if !len.is_multiple_of(BLOCK_SIZE) { return Err("Buffer is not a multiple of the block size"); }
+
+  match lba.checked_add((len / BLOCK_SIZE) as u64) {
+    Some(end) if end <= num_blocks => Ok(()),
+    _                              => Err("Block range out of bounds"),
+  }
+}
+
+/// Return a reference to the global block manager
+pub fn block_manager() -> &'static BlockManager {
+  &BLOCK_MANAGER
+}
+
+/// Register the disk `device` as `name` and its partitions as `name` followed by `p` and their number
+///
+/// A disk without a partition table is not an error; it can still hold a filesystem of its own
+pub fn add_disk(name: &str, device: &'static (dyn interface::BlockDevice + Sync)) -> Result<(), &'static str> {
+  block_manager().register(BlockDeviceDescriptor::new(name, device)?)?;
+
+  if let Err(e) = partition::scan(name, device) { warn!("{}: {}", name, e); }
+
+  Ok(())
+}
+
+/// Register the block device shell commands and the RAM disk as `ram0`
+pub fn init() -> Result<(), &'static str> {
+  shell::register_commands(&commands::COMMANDS)?;
+
+  add_disk("ram0", &RAM_DISK)
+}
+
+/// Scan the disk registered as `name` for partitions again; e.g. after writing a partition table to it
+pub fn rescan(name: &str) -> Result<(), &'static str> {
+  let device = block_manager().find(name).ok_or("No such block device")?;
+
+  partition::scan(name, device)
+}
+
+impl BlockManager {
+  /// Create an instance
+  pub const fn new() -> Self {
+    Self {
+      inner: NullLock::new(BlockManagerInner {
+        descriptors: [None; NUM_BLOCK_DEVICES],
+      }),
+    }
+  }
+
+  /// Register a block device
+  pub fn register(&self, descriptor: BlockDeviceDescriptor) -> Result<(), &'static str> {
+    if descriptor.device.block_size() != BLOCK_SIZE { return Err("Unsupported block size"); }
+
+    self.inner.lock(|i| {
+      if i.descriptors.iter().flatten().any(|d| d.name() == descriptor.name()) { return Err("Block device already registered"); }
+
+      match i.descriptors.iter_mut().find(|d| d.is_none()) {
+        Some(slot) => {
+          *slot = Some(descriptor);
+
+          Ok(())
+        },
+        None => Err("No free block device slots"),
+      }
+    })
+  }
+
+  /// The block device registered as `name`
+  pub fn find(&self, name: &str) -> Option<&'static (dyn interface::BlockDevice + Sync)> {
+    self.inner.lock(|i| {
+      i.
+        descriptors.
+        iter().
+        flatten().
+        find(|d| d.name() == name).
+        map(|d| d.device)
+    })
+  }
+
+  /// Call `f` for every registered block device
+  pub fn for_each(&self, f: impl FnMut(&BlockDeviceDescriptor)) {
+    self.inner.lock(|i| i.descriptors.iter().flatten().for_each(f));
+  }
+
+  /// Print the registered block devices
+  pub fn enumerate(&self) {
+    self.for_each(|d| {
+      let blocks = d.device.num_blocks();
+
+      info!("\t{}: {} blocks ({} KiB)", d.name, blocks, (blocks * BLOCK_SIZE as u64) >> 10);
+    });
+  }
+}
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Block cache
+//!
+//! Keeps the most recently used blocks of a device in memory; the least recently used one is evicted first
+//! Writes go through to the device right away; so there is nothing to flush before a reboot
+
+use super::{
+  check_range,
+  interface,
+  BLOCK_SIZE,
+};
+use crate::synchronization::{
+  interface::Mutex,
+  NullLock,
+};
+
+/// The number of cached blocks; 16 KiB
+const NUM_ENTRIES: usize = 32;
+
+#[derive(Copy, Clone)]
+struct Entry {
+  /// `None` for a free entry
+  lba: Option<u64>,
+
+  /// When the entry was last used; on the cache's own clock
+  last_use: u64,
+
+  data: [u8; BLOCK_SIZE],
+}
+
+struct BlockCacheInner {
+  device: &'static (dyn interface::BlockDevice + Sync),
+  entries: [Entry; NUM_ENTRIES],
+
+  /// Counts up on every access
+  clock: u64,
+
+  hits: u64,
+  misses: u64,
+}
+
+/// A block device that caches the blocks of another one
+pub struct BlockCache {
+  inner: NullLock<BlockCacheInner>,
+}
+
+unsafe impl Sync for BlockCache {}
+
+impl Entry {
+  const fn new() -> Self {
+    Self {
+      lba: None,
+      last_use: 0,
+      data: [0; BLOCK_SIZE],
+    }
+  }
+}
+
+impl BlockCacheInner {
+  const fn new(device: &'static (dyn interface::BlockDevice + Sync)) -> Self {
+    Self {
+      device,
+      entries: [Entry::new(); NUM_ENTRIES],
+      clock: 0,
+      hits: 0,
+      misses: 0,
+    }
+  }
+
+  fn tick(&mut self) -> u64 {
+    self.clock += 1;
+
+    self.clock
+  }
+
+  fn find(&self, lba: u64) -> Option<usize> {
+    self.entries.iter().position(|e| e.lba == Some(lba))
+  }
+
+  /// The entry holding block `lba`; read from the device into the least recently used entry on a miss
+  fn lookup(&mut self, lba: u64) -> Result<usize, &'static str> {
+    let now = self.tick();
+
+    if let Some(index) = self.find(lba) {
+      self.hits += 1;
+      self.entries[index].last_use = now;
+
+      return Ok(index);
+    }
+
+    self.misses += 1;
+
+    let (index, _) = self.
+      entries.
+      iter().
+      enumerate().
+      min_by_key(|(_, e)| (e.lba.is_some(), e.last_use)).
+      ok_or("Block cache has no entries")?;
+
+    let entry = &mut self.entries[index];
+
+    // Don't leave a half-read block behind
+    entry.lba = None;
+    self.device.read_blocks(lba, &mut entry.data)?;
+    entry.lba = Some(lba);
+    entry.last_use = now;
+
+    Ok(index)
+  }
+
+  fn read_blocks(&mut self, lba: u64, buf: &mut [u8]) -> Result<(), &'static str> {
+    check_range(lba, buf.len(), self.device.num_blocks())?;
+
+    for (block, chunk) in buf.chunks_exact_mut(BLOCK_SIZE).enumerate() {
+      let index = self.lookup(lba + block as u64)?;
+
+      chunk.copy_from_slice(&self.entries[index].data);
+    }
+
+    Ok(())
+  }
+
+  fn write_blocks(&mut self, lba: u64, buf: &[u8]) -> Result<(), &'static str> {
+    check_range(lba, buf.len(), self.device.num_blocks())?;
+
+    let result = self.device.write_blocks(lba, buf);
+
+    // Keep cached copies in line with what the device holds now; after a failed write, that is unknown
+    for (block, chunk) in buf.chunks_exact(BLOCK_SIZE).enumerate() {
+      let Some(index) = self.find(lba + block as u64) else { continue; };
+
+      match result {
+        Ok(()) => self.entries[index].data.copy_from_slice(chunk),
+        Err(_) => self.entries[index].lba = None,
+      }
+    }
+
+    result
+  }
+}
+
+impl BlockCache {
+  /// Create an instance that caches the blocks of `device`
+  pub const fn new(device: &'static (dyn interface::BlockDevice + Sync)) -> Self {
+    Self {
+      inner: NullLock::new(BlockCacheInner::new(device)),
+    }
+  }
+
+  /// The number of reads served from and missing the cache
+  #[allow(dead_code)]
+  pub fn statistics(&self) -> (u64, u64) {
+    self.inner.lock(|i| (i.hits, i.misses))
+  }
+
+  /// Forget all cached blocks; e.g. after the card was swapped
+  #[allow(dead_code)]
+  pub fn invalidate(&self) {
+    self.inner.lock(|i| i.entries.iter_mut().for_each(|e| e.lba = None));
+  }
+}
+
+impl interface::BlockDevice for BlockCache {
+  fn block_size(&self) -> usize {
+    BLOCK_SIZE
+  }
+
+  fn num_blocks(&self) -> u64 {
+    self.inner.lock(|i| i.device.num_blocks())
+  }
+
+  fn read_blocks(&self, lba: u64, buf: &mut [u8]) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.read_blocks(lba, buf))
+  }
+
+  fn write_blocks(&self, lba: u64, buf: &[u8]) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.write_blocks(lba, buf))
+  }
+}
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Block device shell commands
+
+use core::str::SplitWhitespace;
+
+use crate::{
+  block,
+  print,
+  println,
+  shell::{
+    parse_number,
+    Command,
+  },
+};
+
+pub const COMMANDS: [Command; 4] = [
+  Command::new("lsblk",    "List the block devices",                            lsblk),
+  Command::new("readblk",  "readblk <dev> <lba>: Hex dump a block",             readblk),
+  Command::new("writeblk", "writeblk <dev> <lba> <off> <hex>: Patch a block",   writeblk),
+  Command::new("partscan", "partscan <dev>: Rescan a disk's partition table",   partscan),
+];
+
+fn lsblk(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  block::block_manager().enumerate();
+
+  Ok(())
+}
+
+fn readblk(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let name = args.next().ok_or("Missing argument")?;
+  let device = block::block_manager().find(name).ok_or("No such block device")?;
+  let lba = parse_number(args.next())? as u64;
+
+  let mut buf = [0; block::BLOCK_SIZE];
+
+  device.read_blocks(lba, &mut buf)?;
+
+  for (offset, line) in buf.chunks(16).enumerate() {
+    print!("{:04x}:", offset * 16);
+    for b in line { print!(" {:02x}", b); }
+    println!();
+  }
+
+  Ok(())
+}
+
+/// Read-modify-write; e.g. to put a partition table on `ram0`
+fn writeblk(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let name = args.next().ok_or("Missing argument")?;
+  let device = block::block_manager().find(name).ok_or("No such block device")?;
+  let lba = parse_number(args.next())? as u64;
+  let offset = parse_number(args.next())?;
+  let hex = args.next().ok_or("Missing argument")?;
+
+  if !hex.len().is_multiple_of(2) { return Err("Odd number of hex digits"); }
+
+  let mut buf = [0; block::BLOCK_SIZE];
+
+  device.read_blocks(lba, &mut buf)?;
+
+  let patch = buf.get_mut(offset..offset + hex.len() / 2).ok_or("Patch does not fit in the block")?;
+
+  for (i, b) in patch.iter_mut().enumerate() {
+    *b = hex.get(i * 2..i * 2 + 2).and_then(|h| u8::from_str_radix(h, 16).ok()).ok_or("Invalid hex digits")?;
+  }
+
+  device.write_blocks(lba, &buf)
+}
+
+fn partscan(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  block::rescan(args.next().ok_or("Missing argument")?)
+}
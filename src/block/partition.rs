@@ -0,0 +1,369 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! MBR and GPT partition tables
+//!
+//! A disk whose MBR holds a protective entry (type 0xEE) has a GPT; otherwise its primary MBR entries are used
+//! Logical partitions inside an extended MBR partition are not supported
+//!
+//! Partitions come from a static pool; they are never removed
+//! A rescan moves them to their new place in the table; a partition that is gone from it is left without blocks
+
+use core::{
+  fmt::Write,
+  ptr,
+};
+
+use super::{
+  block_manager,
+  check_range,
+  interface,
+  BlockDeviceDescriptor,
+  Name,
+  BLOCK_SIZE,
+};
+use crate::{
+  info,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  warn,
+};
+
+/// The number of partitions across all disks
+const NUM_PARTITIONS: usize = 8;
+
+/// The most partitions taken from one table
+const MAX_TABLE_ENTRIES: usize = 8;
+
+const MBR_SIGNATURE_OFFSET: usize = 510;
+const MBR_SIGNATURE:        u16   = 0xaa55;
+const MBR_ENTRIES_OFFSET:   usize = 446;
+const MBR_ENTRY_SIZE:       usize = 16;
+const MBR_NUM_ENTRIES:      usize = 4;
+
+const MBR_TYPE_EMPTY:          u8 = 0x00;
+const MBR_TYPE_EXTENDED_CHS:   u8 = 0x05;
+const MBR_TYPE_EXTENDED_LBA:   u8 = 0x0f;
+const MBR_TYPE_GPT_PROTECTIVE: u8 = 0xee;
+
+const GPT_HEADER_LBA: u64      = 1;
+const GPT_SIGNATURE:  &[u8; 8] = b"EFI PART";
+
+/// The header fields up to the entry array CRC
+const GPT_MIN_HEADER_SIZE: usize = 92;
+const GPT_MIN_ENTRY_SIZE:  usize = 128;
+
+struct PartitionInner {
+  /// `None` for a free pool slot
+  parent: Option<&'static (dyn interface::BlockDevice + Sync)>,
+  first_lba: u64,
+  num_blocks: u64,
+}
+
+/// A range of blocks on a disk
+pub struct Partition {
+  inner: NullLock<PartitionInner>,
+}
+
+unsafe impl Sync for Partition {}
+
+static PARTITIONS: [Partition; NUM_PARTITIONS] = [const { Partition::new() }; NUM_PARTITIONS];
+
+/// A partition table entry; the first block and the number of blocks
+#[derive(Copy, Clone)]
+struct Entry {
+  number: usize,
+  first_lba: u64,
+  num_blocks: u64,
+}
+
+/// What block 0 of a disk holds; only ever on the stack, so the size difference doesn't matter
+#[allow(clippy::large_enum_variant)]
+enum Mbr {
+  /// No boot signature; the disk is not partitioned
+  None,
+
+  /// A protective MBR; the partitions are in the GPT
+  Protective,
+
+  Table(Table),
+}
+
+/// The entries found in a partition table
+struct Table {
+  kind: &'static str,
+  entries: [Option<Entry>; MAX_TABLE_ENTRIES],
+}
+
+impl Partition {
+  const fn new() -> Self {
+    Self {
+      inner: NullLock::new(PartitionInner {
+        parent: None,
+        first_lba: 0,
+        num_blocks: 0,
+      }),
+    }
+  }
+
+  /// Take this slot for `entry` of `parent`; fails if it is taken already
+  fn claim(&self, parent: &'static (dyn interface::BlockDevice + Sync), entry: &Entry) -> bool {
+    self.inner.lock(|i| {
+      if i.parent.is_some() { return false; }
+
+      i.parent = Some(parent);
+      i.first_lba = entry.first_lba;
+      i.num_blocks = entry.num_blocks;
+
+      true
+    })
+  }
+
+  fn release(&self) {
+    self.inner.lock(|i| i.parent = None);
+  }
+
+  /// Move this partition to the blocks of `entry`; if it is one of `parent`'s
+  fn update(&self, parent: &'static (dyn interface::BlockDevice + Sync), entry: &Entry) -> bool {
+    self.inner.lock(|i| {
+      if !i.parent.is_some_and(|p| ptr::addr_eq(p, parent)) { return false; }
+
+      i.first_lba = entry.first_lba;
+      i.num_blocks = entry.num_blocks;
+
+      true
+    })
+  }
+
+  /// Leave this partition without blocks; if it is one of `parent`'s
+  fn clear(&self, parent: &'static (dyn interface::BlockDevice + Sync)) {
+    self.inner.lock(|i| {
+      if i.parent.is_some_and(|p| ptr::addr_eq(p, parent)) { i.num_blocks = 0; }
+    });
+  }
+
+  /// The parent and the parent's block `lba` of a transfer of `len` bytes
+  fn translate(&self, lba: u64, len: usize) -> Result<(&'static (dyn interface::BlockDevice + Sync), u64), &'static str> {
+    self.inner.lock(|i| {
+      let parent = i.parent.ok_or("Partition is not in use")?;
+
+      check_range(lba, len, i.num_blocks)?;
+
+      Ok((parent, i.first_lba + lba))
+    })
+  }
+}
+
+impl interface::BlockDevice for Partition {
+  fn block_size(&self) -> usize {
+    BLOCK_SIZE
+  }
+
+  fn num_blocks(&self) -> u64 {
+    self.inner.lock(|i| i.num_blocks)
+  }
+
+  fn read_blocks(&self, lba: u64, buf: &mut [u8]) -> Result<(), &'static str> {
+    let (parent, lba) = self.translate(lba, buf.len())?;
+
+    parent.read_blocks(lba, buf)
+  }
+
+  fn write_blocks(&self, lba: u64, buf: &[u8]) -> Result<(), &'static str> {
+    let (parent, lba) = self.translate(lba, buf.len())?;
+
+    parent.write_blocks(lba, buf)
+  }
+}
+
+fn u16_at(buf: &[u8], offset: usize) -> u16 {
+  u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+  u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+fn u64_at(buf: &[u8], offset: usize) -> u64 {
+  u32_at(buf, offset) as u64 | ((u32_at(buf, offset + 4) as u64) << 32)
+}
+
+/// Add `bytes` to the running CRC-32 (IEEE) `crc`; start with `!0` and invert the result
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+  for &b in bytes {
+    crc ^= b as u32;
+
+    for _ in 0..8 {
+      crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+    }
+  }
+
+  crc
+}
+
+impl Table {
+  const fn new(kind: &'static str) -> Self {
+    Self { kind, entries: [None; MAX_TABLE_ENTRIES] }
+  }
+
+  fn push(&mut self, entry: Entry) -> Result<(), &'static str> {
+    let slot = self.entries.iter_mut().find(|e| e.is_none()).ok_or("Too many partitions")?;
+
+    *slot = Some(entry);
+
+    Ok(())
+  }
+
+  /// Parse the MBR in `mbr`
+  fn parse_mbr(mbr: &[u8]) -> Result<Mbr, &'static str> {
+    if u16_at(mbr, MBR_SIGNATURE_OFFSET) != MBR_SIGNATURE { return Ok(Mbr::None); }
+
+    let mut table = Self::new("MBR");
+
+    for number in 1..=MBR_NUM_ENTRIES {
+      let entry = &mbr[MBR_ENTRIES_OFFSET + (number - 1) * MBR_ENTRY_SIZE..][..MBR_ENTRY_SIZE];
+
+      match entry[4] {
+        MBR_TYPE_GPT_PROTECTIVE                       => return Ok(Mbr::Protective),
+        MBR_TYPE_EMPTY                                => continue,
+        MBR_TYPE_EXTENDED_CHS | MBR_TYPE_EXTENDED_LBA => {
+          warn!("Skipping extended partition {}", number);
+
+          continue;
+        },
+        _ => (),
+      }
+
+      table.push(Entry {
+        number,
+        first_lba: u32_at(entry, 8) as u64,
+        num_blocks: u32_at(entry, 12) as u64,
+      })?;
+    }
+
+    Ok(Mbr::Table(table))
+  }
+
+  /// Read the GPT from `device`; both the header and the entry array CRCs must match
+  fn read_gpt(device: &(dyn interface::BlockDevice + Sync)) -> Result<Self, &'static str> {
+    let mut header = [0; BLOCK_SIZE];
+
+    device.read_blocks(GPT_HEADER_LBA, &mut header)?;
+
+    if &header[..8] != GPT_SIGNATURE { return Err("No GPT header"); }
+
+    let header_size = u32_at(&header, 12) as usize;
+
+    if !(GPT_MIN_HEADER_SIZE..=BLOCK_SIZE).contains(&header_size) { return Err("Invalid GPT header size"); }
+
+    let header_crc = u32_at(&header, 16);
+
+    // The CRC is computed with its own field zeroed
+    header[16..20].fill(0);
+
+    if !crc32_update(!0, &header[..header_size]) != header_crc { return Err("GPT header CRC mismatch"); }
+
+    let entries_lba = u64_at(&header, 72);
+    let num_entries = u32_at(&header, 80) as usize;
+    let entry_size = u32_at(&header, 84) as usize;
+    let entries_crc = u32_at(&header, 88);
+
+    if entry_size < GPT_MIN_ENTRY_SIZE || !BLOCK_SIZE.is_multiple_of(entry_size) { return Err("Unsupported GPT entry size"); }
+
+    let mut table = Self::new("GPT");
+    let mut crc = !0;
+    let mut block = [0; BLOCK_SIZE];
+    let entries_per_block = BLOCK_SIZE / entry_size;
+
+    for index in 0..num_entries {
+      if index.is_multiple_of(entries_per_block) {
+        device.read_blocks(entries_lba + (index / entries_per_block) as u64, &mut block)?;
+      }
+
+      let entry = &block[(index % entries_per_block) * entry_size..][..entry_size];
+
+      crc = crc32_update(crc, entry);
+
+      // An all-zero type GUID marks an unused entry
+      if entry[..16].iter().all(|&b| b == 0) { continue; }
+
+      let first_lba = u64_at(entry, 32);
+      let last_lba = u64_at(entry, 40);
+
+      if last_lba < first_lba { return Err("Invalid GPT entry"); }
+
+      table.push(Entry { number: index + 1, first_lba, num_blocks: last_lba - first_lba + 1 })?;
+    }
+
+    if !crc != entries_crc { return Err("GPT entry array CRC mismatch"); }
+
+    Ok(table)
+  }
+}
+
+/// Register the partition `entry` of the disk `name` as a block device
+fn register(name: &str, device: &'static (dyn interface::BlockDevice + Sync), entry: &Entry) -> Result<(), &'static str> {
+  let mut partition_name = Name::try_from_str(name).map_err(|_| "Block device name too long")?;
+
+  write!(partition_name, "p{}", entry.number).map_err(|_| "Block device name too long")?;
+
+  if entry.num_blocks == 0 || entry.first_lba.saturating_add(entry.num_blocks) > device.num_blocks() {
+    return Err("Partition lies outside of the disk");
+  }
+
+  // Registered by an earlier scan; the table may have changed since
+  if let Some(existing) = block_manager().find(partition_name.as_str()) {
+    return PARTITIONS.
+      iter().
+      find(|p| ptr::addr_eq(*p, existing)).
+      filter(|p| p.update(device, entry)).
+      map(|_| ()).
+      ok_or("Block device name already taken");
+  }
+
+  let partition = PARTITIONS.iter().find(|p| p.claim(device, entry)).ok_or("No free partition slots")?;
+  let result = BlockDeviceDescriptor::new(partition_name.as_str(), partition).
+    and_then(|descriptor| block_manager().register(descriptor));
+
+  if result.is_err() { partition.release(); }
+
+  result
+}
+
+/// Read the partition table of the disk `device` and register its partitions; the disk is registered as `name`
+pub fn scan(name: &str, device: &'static (dyn interface::BlockDevice + Sync)) -> Result<(), &'static str> {
+  let mut mbr = [0; BLOCK_SIZE];
+
+  device.read_blocks(0, &mut mbr)?;
+
+  let table = match Table::parse_mbr(&mbr)? {
+    Mbr::Table(table) => Some(table),
+    Mbr::Protective   => Some(Table::read_gpt(device)?),
+    Mbr::None         => None,
+  };
+
+  // Partitions from an earlier scan keep no blocks unless they are still in the table
+  PARTITIONS.iter().for_each(|p| p.clear(device));
+
+  let Some(table) = table else {
+    info!("{}: no partition table", name);
+
+    return Ok(());
+  };
+
+  let mut found = 0;
+
+  for entry in table.entries.iter().flatten() {
+    match register(name, device, entry) {
+      Ok(()) => found += 1,
+      Err(e) => warn!("{}: partition {}: {}", name, entry.number, e),
+    }
+  }
+
+  info!("{}: {} with {} partitions", name, table.kind, found);
+
+  Ok(())
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! RAM disk
+//!
+//! A block device backed by a static array; its contents are lost on reboot
+
+use super::{
+  check_range,
+  interface,
+  BLOCK_SIZE,
+};
+use crate::synchronization::{
+  interface::Mutex,
+  NullLock,
+};
+
+/// A RAM disk of `BLOCKS` blocks; starts out zeroed
+pub struct RamDisk<const BLOCKS: usize> {
+  inner: NullLock<[[u8; BLOCK_SIZE]; BLOCKS]>,
+}
+
+unsafe impl<const BLOCKS: usize> Sync for RamDisk<BLOCKS> {}
+
+impl<const BLOCKS: usize> RamDisk<BLOCKS> {
+  /// Create an instance
+  pub const fn new() -> Self {
+    Self {
+      inner: NullLock::new([[0; BLOCK_SIZE]; BLOCKS]),
+    }
+  }
+}
+
+impl<const BLOCKS: usize> interface::BlockDevice for RamDisk<BLOCKS> {
+  fn block_size(&self) -> usize {
+    BLOCK_SIZE
+  }
+
+  fn num_blocks(&self) -> u64 {
+    BLOCKS as u64
+  }
+
+  fn read_blocks(&self, lba: u64, buf: &mut [u8]) -> Result<(), &'static str> {
+    check_range(lba, buf.len(), BLOCKS as u64)?;
+
+    self.inner.lock(|blocks| {
+      for (src, dst) in blocks[lba as usize..].iter().zip(buf.chunks_exact_mut(BLOCK_SIZE)) {
+        dst.copy_from_slice(src);
+      }
+    });
+
+    Ok(())
+  }
+
+  fn write_blocks(&self, lba: u64, buf: &[u8]) -> Result<(), &'static str> {
+    check_range(lba, buf.len(), BLOCKS as u64)?;
+
+    self.inner.lock(|blocks| {
+      for (dst, src) in blocks[lba as usize..].iter_mut().zip(buf.chunks_exact(BLOCK_SIZE)) {
+        dst.copy_from_slice(src);
+      }
+    });
+
+    Ok(())
+  }
+}
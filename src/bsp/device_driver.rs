@@ -4,9 +4,14 @@
 
 //! Device drivers
 
+#[cfg(feature = "bsp_rpi4")]
+mod arm;
 #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
 mod bcm;
 mod common;
 
+#[cfg(feature = "bsp_rpi4")]
+pub use arm::*;
 #[cfg(any(feature = "bsp_rpi3", feature = "bsp_rpi4"))]
-pub use bcm::*;
\ No newline at end of file
+pub use bcm::*;
+pub use common::UartConfig;
\ No newline at end of file
@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! GICv2 driver
+//!
+//! The GIC has two parts:
+//! - The distributor (GICD); which prioritizes interrupts and routes them to the cores
+//! - The CPU interface (GICC); one per core, through which a core acknowledges and completes interrupts
+//!
+//! Interrupt IDs 0 to 15 are SGIs (software generated), 16 to 31 PPIs (private per core) and 32 and up SPIs (shared peripherals)
+//! All SPIs are routed to the boot core
+
+use tock_registers::{
+  interfaces::{
+    Readable,
+    Writeable,
+  },
+  register_bitfields,
+  register_structs,
+  registers::{
+    ReadOnly,
+    ReadWrite,
+  },
+};
+
+use crate::{
+  bsp::device_driver::common::MMIODerefWrapper,
+  common::BoundedUsize,
+  driver,
+  exception::asynchronous::{
+    interface,
+    IRQHandlerDescriptor,
+  },
+  info,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+  },
+};
+
+register_bitfields! {
+  u32,
+
+  /// Distributor Control Register
+  GICD_CTLR [
+    /// Forward pending interrupts to the CPU interfaces
+    Enable OFFSET(0) NUMBITS(1) []
+  ],
+
+  /// Interrupt Controller Type Register
+  GICD_TYPER [
+    /// The number of implemented interrupts is `32 * (ITLinesNumber + 1)`
+    ITLinesNumber OFFSET(0) NUMBITS(5) []
+  ],
+
+  /// Interrupt Processor Targets Registers; one byte per interrupt
+  GICD_ITARGETSR [
+    Offset3 OFFSET(24) NUMBITS(8) [],
+    Offset2 OFFSET(16) NUMBITS(8) [],
+    Offset1 OFFSET(8)  NUMBITS(8) [],
+    Offset0 OFFSET(0)  NUMBITS(8) []
+  ],
+
+  /// CPU Interface Control Register
+  GICC_CTLR [
+    /// Signal interrupts to the core
+    Enable OFFSET(0) NUMBITS(1) []
+  ],
+
+  /// Interrupt Priority Mask Register
+  GICC_PMR [
+    /// Only interrupts with a higher priority (lower value) are signaled
+    Priority OFFSET(0) NUMBITS(8) []
+  ],
+
+  /// Interrupt Acknowledge Register
+  GICC_IAR [
+    /// The ID of the highest priority pending interrupt; 1023 if there is none
+    InterruptID OFFSET(0) NUMBITS(10) []
+  ],
+
+  /// End of Interrupt Register
+  GICC_EOIR [
+    /// The ID of the completed interrupt
+    EOIINTID OFFSET(0) NUMBITS(10) []
+  ]
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  DistributorRegisterBlock {
+    (0x000 => CTLR: ReadWrite<u32, GICD_CTLR::Register>),
+    (0x004 => TYPER: ReadOnly<u32, GICD_TYPER::Register>),
+    (0x008 => _reserved1),
+    /// Set-enable; 1 bit per interrupt, writing 0 has no effect
+    (0x100 => ISENABLER: [ReadWrite<u32>; 32]),
+    (0x180 => _reserved2),
+    /// The first 8 registers are read-only and banked per core; they cover SGIs and PPIs
+    (0x800 => ITARGETSR: [ReadWrite<u32, GICD_ITARGETSR::Register>; 256]),
+    (0xc00 => @END),
+  }
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  CPUInterfaceRegisterBlock {
+    (0x000 => CTLR: ReadWrite<u32, GICC_CTLR::Register>),
+    (0x004 => PMR: ReadWrite<u32, GICC_PMR::Register>),
+    (0x008 => _reserved1),
+    (0x00c => IAR: ReadOnly<u32, GICC_IAR::Register>),
+    (0x010 => EOIR: ReadWrite<u32, GICC_EOIR::Register>),
+    (0x014 => @END),
+  }
+}
+
+/// Abstraction for the distributor's MMIO registers
+type DistributorRegisters = MMIODerefWrapper<DistributorRegisterBlock>;
+
+/// Abstraction for the CPU interface's MMIO registers
+type CPUInterfaceRegisters = MMIODerefWrapper<CPUInterfaceRegisterBlock>;
+
+/// The GICv2 IRQ number type
+pub type IRQNumber = BoundedUsize<{ GICv2::MAX_IRQ_NUMBER }>;
+
+const NUM_IRQS: usize = GICv2::MAX_IRQ_NUMBER + 1;
+
+struct GICv2Inner {
+  gicd: DistributorRegisters,
+  gicc: CPUInterfaceRegisters,
+  handler_table: [Option<IRQHandlerDescriptor<IRQNumber>>; NUM_IRQS],
+}
+
+/// Representation of the GIC
+pub struct GICv2 {
+  inner: IRQSafeNullLock<GICv2Inner>,
+}
+
+unsafe impl Sync for GICv2 {}
+
+impl GICv2Inner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide valid MMIO start addresses
+  const unsafe fn new(gicd_mmio_start_addr: usize, gicc_mmio_start_addr: usize) -> Self {
+    Self {
+      gicd: unsafe { DistributorRegisters::new(gicd_mmio_start_addr) },
+      gicc: unsafe { CPUInterfaceRegisters::new(gicc_mmio_start_addr) },
+      handler_table: [None; NUM_IRQS],
+    }
+  }
+
+  /// The number of ITARGETSR registers that are implemented; 4 interrupts per register
+  fn num_itargetsr(&self) -> usize {
+    let num_irqs = ((self.gicd.TYPER.read(GICD_TYPER::ITLinesNumber) as usize) + 1) * 32;
+
+    num_irqs / 4
+  }
+
+  /// Route all SPIs to the executing core and enable the distributor
+  fn init_distributor(&mut self) {
+    // Reading any of the banked registers returns the mask of the executing core
+    let mask = self.gicd.ITARGETSR[0].read(GICD_ITARGETSR::Offset0);
+
+    for i in 8..self.num_itargetsr() {
+      self.gicd.ITARGETSR[i].write(
+        GICD_ITARGETSR::Offset3.val(mask)
+        +
+        GICD_ITARGETSR::Offset2.val(mask)
+        +
+        GICD_ITARGETSR::Offset1.val(mask)
+        +
+        GICD_ITARGETSR::Offset0.val(mask)
+      );
+    }
+
+    self.gicd.CTLR.write(GICD_CTLR::Enable::SET);
+  }
+
+  /// Let all priorities through and enable the executing core's CPU interface
+  fn init_cpu_interface(&mut self) {
+    self.gicc.PMR.write(GICC_PMR::Priority.val(255));
+    self.gicc.CTLR.write(GICC_CTLR::Enable::SET);
+  }
+}
+
+impl GICv2 {
+  pub const COMPATIBLE: &'static str = "GICv2 (ARM Generic Interrupt Controller v2)";
+
+  /// Architecturally up to 1019; but the BCM2711 doesn't use anything beyond 255, so keep the handler table small
+  pub const MAX_IRQ_NUMBER: usize = 255;
+
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide valid MMIO start addresses
+  pub const unsafe fn new(gicd_mmio_start_addr: usize, gicc_mmio_start_addr: usize) -> Self {
+    Self {
+      inner: IRQSafeNullLock::new(unsafe { GICv2Inner::new(gicd_mmio_start_addr, gicc_mmio_start_addr) }),
+    }
+  }
+}
+
+impl driver::interface::DeviceDriver for GICv2 {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+
+  unsafe fn init(&self) -> Result<(), &'static str> {
+    self.inner.lock(|i| {
+      i.init_distributor();
+      i.init_cpu_interface();
+    });
+
+    Ok(())
+  }
+}
+
+impl interface::IRQManager for GICv2 {
+  type IRQNumberType = IRQNumber;
+
+  fn register_handler(&self, descriptor: IRQHandlerDescriptor<Self::IRQNumberType>) -> Result<(), &'static str> {
+    let number = descriptor.number().get();
+
+    self.inner.lock(|i| {
+      if i.handler_table[number].is_some() { return Err("IRQ handler already registered"); }
+
+      i.handler_table[number] = Some(descriptor);
+
+      Ok(())
+    })
+  }
+
+  fn enable(&self, irq: &Self::IRQNumberType) {
+    let number = irq.get();
+
+    self.inner.lock(|i| i.gicd.ISENABLER[number / 32].set(1 << (number % 32)));
+  }
+
+  fn handle_pending_irqs(&self) {
+    // Acknowledging the interrupt marks it active; so it is not signaled again until completed
+    let number = self.inner.lock(|i| i.gicc.IAR.read(GICC_IAR::InterruptID)) as usize;
+
+    // Spurious interrupt; there is nothing to complete either
+    if number > Self::MAX_IRQ_NUMBER { return; }
+
+    match self.inner.lock(|i| i.handler_table[number]) {
+      None             => panic!("No handler registered for IRQ {}", number),
+      Some(descriptor) => descriptor.handler().handle().expect("Error handling IRQ"),
+    }
+
+    self.inner.lock(|i| i.gicc.EOIR.write(GICC_EOIR::EOIINTID.val(number as u32)));
+  }
+
+  fn print_handler(&self) {
+    self.inner.lock(|i| {
+      i.
+        handler_table.
+        iter().
+        flatten().
+        for_each(|d| info!("\t{}", d))
+    });
+  }
+}
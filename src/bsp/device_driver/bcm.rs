@@ -4,8 +4,30 @@
 
 //! Top-level BCM driver
 
+mod bcm2xxx_emmc;
+mod bcm2xxx_framebuffer;
 mod bcm2xxx_gpio;
+#[cfg(feature = "bsp_rpi3")]
+mod bcm2xxx_interrupt_controller;
+mod bcm2xxx_mailbox;
+mod bcm2xxx_mini_uart;
 mod bcm2xxx_pl011_uart;
+mod bcm2xxx_power_management;
+#[cfg(feature = "bsp_rpi3")]
+mod bcm2xxx_rng;
+#[cfg(feature = "bsp_rpi4")]
+mod bcm2xxx_rng200;
 
+pub use bcm2xxx_emmc::*;
+pub use bcm2xxx_framebuffer::*;
 pub use bcm2xxx_gpio::*;
-pub use bcm2xxx_pl011_uart::*;
\ No newline at end of file
+#[cfg(feature = "bsp_rpi3")]
+pub use bcm2xxx_interrupt_controller::*;
+pub use bcm2xxx_mailbox::*;
+pub use bcm2xxx_mini_uart::*;
+pub use bcm2xxx_pl011_uart::*;
+pub use bcm2xxx_power_management::*;
+#[cfg(feature = "bsp_rpi3")]
+pub use bcm2xxx_rng::*;
+#[cfg(feature = "bsp_rpi4")]
+pub use bcm2xxx_rng200::*;
\ No newline at end of file
@@ -0,0 +1,611 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! EMMC (Arasan SDHCI) driver
+//!
+//! Initialises an SD card and transfers 512-byte blocks through the DATA register (PIO)
+//! Init follows the SD physical layer spec:
+//! - Reset the card to idle (CMD0) and check its voltage range (CMD8); cards that don't answer CMD8 are version 1
+//! - Repeat ACMD41 until the card is powered up; its answer tells whether it uses block (SDHC/SDXC) or byte (SDSC) addresses
+//! - Read its CID (CMD2), get its relative card address (CMD3), read its CSD (CMD9) for the capacity and select it (CMD7)
+//! - Switch to a 4 bit bus (ACMD6) and the 25 MHz default speed
+//!
+//! Interrupts are only used as status flags; the driver polls them
+
+use core::time::Duration;
+
+use tock_registers::{
+  fields::FieldValue,
+  interfaces::{
+    ReadWriteable,
+    Readable,
+    Writeable,
+  },
+  register_bitfields,
+  register_structs,
+  registers::{
+    ReadOnly,
+    ReadWrite,
+  },
+};
+
+use crate::{
+  block::{
+    self,
+    BLOCK_SIZE,
+  },
+  bsp::device_driver::{
+    common::MMIODerefWrapper,
+    Clock,
+    Mailbox,
+  },
+  cpu,
+  driver,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  time,
+};
+
+register_bitfields! {
+  u32,
+
+  /// Block Size and Count Register
+  BLKSIZECNT [
+    BLKCNT  OFFSET(16) NUMBITS(16) [],
+    BLKSIZE OFFSET(0)  NUMBITS(10) []
+  ],
+
+  /// Command and Transfer Mode Register
+  CMDTM [
+    CMD_INDEX OFFSET(24) NUMBITS(6) [],
+
+    CMD_ISDATA OFFSET(21) NUMBITS(1) [],
+
+    /// Check the response's command index
+    CMD_IXCHK_EN OFFSET(20) NUMBITS(1) [],
+
+    /// Check the response's CRC
+    CMD_CRCCHK_EN OFFSET(19) NUMBITS(1) [],
+
+    CMD_RSPNS_TYPE OFFSET(16) NUMBITS(2) [
+      None       = 0b00,
+      Bits136    = 0b01,
+      Bits48     = 0b10,
+      Bits48Busy = 0b11
+    ],
+
+    TM_MULTI_BLOCK OFFSET(5) NUMBITS(1) [],
+
+    TM_DAT_DIR OFFSET(4) NUMBITS(1) [
+      HostToCard = 0,
+      CardToHost = 1
+    ],
+
+    /// Stop multi block transfers with CMD12 automatically
+    TM_AUTO_CMD_EN OFFSET(2) NUMBITS(2) [
+      None  = 0b00,
+      Cmd12 = 0b01
+    ],
+
+    TM_BLKCNT_EN OFFSET(1) NUMBITS(1) []
+  ],
+
+  /// Status Register
+  STATUS [
+    DAT_INHIBIT OFFSET(1) NUMBITS(1) [],
+    CMD_INHIBIT OFFSET(0) NUMBITS(1) []
+  ],
+
+  /// Host Configuration Register 0
+  CONTROL0 [
+    /// SD bus power; the BCM2711's EMMC2 needs it switched on
+    SD_BUS_POWER OFFSET(8) NUMBITS(4) [
+      Off = 0b0000,
+      V3_3 = 0b1111
+    ],
+
+    HCTL_DWIDTH OFFSET(1) NUMBITS(1) [
+      OneBit  = 0,
+      FourBit = 1
+    ]
+  ],
+
+  /// Host Configuration Register 1
+  CONTROL1 [
+    SRST_DATA OFFSET(26) NUMBITS(1) [],
+    SRST_CMD  OFFSET(25) NUMBITS(1) [],
+    SRST_HC   OFFSET(24) NUMBITS(1) [],
+
+    /// Data timeout; `TMCLK * 2^(DATA_TOUNIT + 13)`
+    DATA_TOUNIT OFFSET(16) NUMBITS(4) [
+      Max = 0b1110
+    ],
+
+    /// The lower 8 bits of the clock divider
+    CLK_FREQ8 OFFSET(8) NUMBITS(8) [],
+
+    /// The upper 2 bits of the clock divider
+    CLK_FREQ_MS2 OFFSET(6) NUMBITS(2) [],
+
+    CLK_EN     OFFSET(2) NUMBITS(1) [],
+    CLK_STABLE OFFSET(1) NUMBITS(1) [],
+    CLK_INTLEN OFFSET(0) NUMBITS(1) []
+  ],
+
+  /// Interrupt Flag Register; written 1s clear
+  INTERRUPT [
+    /// Any of the error bits 16 and up
+    ERR OFFSET(15) NUMBITS(1) [],
+
+    READ_RDY  OFFSET(5) NUMBITS(1) [],
+    WRITE_RDY OFFSET(4) NUMBITS(1) [],
+    DATA_DONE OFFSET(1) NUMBITS(1) [],
+    CMD_DONE  OFFSET(0) NUMBITS(1) []
+  ],
+
+  /// Slot Interrupt Status and Version Register
+  SLOTISR_VER [
+    /// 0 = spec 1.0, 1 = 2.0, 2 = 3.0
+    SDVERSION OFFSET(16) NUMBITS(8) []
+  ]
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  RegisterBlock {
+    (0x00 => _reserved1),
+    (0x04 => BLKSIZECNT: ReadWrite<u32, BLKSIZECNT::Register>),
+    (0x08 => ARG1: ReadWrite<u32>),
+    (0x0c => CMDTM: ReadWrite<u32, CMDTM::Register>),
+    (0x10 => RESP: [ReadOnly<u32>; 4]),
+    (0x20 => DATA: ReadWrite<u32>),
+    (0x24 => STATUS: ReadOnly<u32, STATUS::Register>),
+    (0x28 => CONTROL0: ReadWrite<u32, CONTROL0::Register>),
+    (0x2c => CONTROL1: ReadWrite<u32, CONTROL1::Register>),
+    (0x30 => INTERRUPT: ReadWrite<u32, INTERRUPT::Register>),
+    (0x34 => IRPT_MASK: ReadWrite<u32>),
+    (0x38 => IRPT_EN: ReadWrite<u32>),
+    (0x3c => _reserved2),
+    (0xfc => SLOTISR_VER: ReadOnly<u32, SLOTISR_VER::Register>),
+    (0x100 => @END),
+  }
+}
+
+/// Abstraction for the associated MMIO registers
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// How long a reset, command or block transfer may take
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long the card may take to power up
+const POWER_UP_TIMEOUT: Duration = Duration::from_secs(2);
+
+const IDENTIFICATION_CLOCK_HZ: u32 = 400_000;
+const DEFAULT_SPEED_CLOCK_HZ:  u32 = 25_000_000;
+
+/// CMD8 argument; 2.7 to 3.6 V and a check pattern the card echoes
+const IF_COND_3V3: u32 = 0x1aa;
+
+/// ACMD41 argument bits
+const OCR_VOLTAGE_WINDOW: u32 = 0x00ff_8000;
+const OCR_HCS:            u32 = 1 << 30;
+const OCR_BUSY:           u32 = 1 << 31;
+
+/// ACMD6 argument
+const BUS_WIDTH_4: u32 = 0b10;
+
+const SDVERSION_3: u32 = 2;
+
+/// SD commands; the `App` ones must be preceded by `AppCmd`
+#[derive(Copy, Clone)]
+enum Command {
+  GoIdleState,
+  AllSendCid,
+  SendRelativeAddr,
+  SelectCard,
+  SendIfCond,
+  SendCsd,
+  SetBlockLen,
+  ReadSingleBlock,
+  ReadMultipleBlock,
+  WriteBlock,
+  WriteMultipleBlock,
+  AppCmd,
+  AppSetBusWidth,
+  AppSendOpCond,
+}
+
+/// Transfer direction
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Direction {
+  Read,
+  Write,
+}
+
+/// What init learned about the card
+#[derive(Copy, Clone)]
+struct Card {
+  /// Relative card address
+  rca: u32,
+
+  /// SDHC/SDXC cards take block addresses; SDSC cards byte addresses
+  block_addressing: bool,
+
+  num_blocks: u64,
+}
+
+struct EMMCInner {
+  registers: Registers,
+  mailbox: &'static Mailbox,
+  clock: Clock,
+
+  /// `None` until init found a card
+  card: Option<Card>,
+}
+
+/// Representation of the EMMC controller
+pub struct EMMC {
+  inner: NullLock<EMMCInner>,
+}
+
+unsafe impl Sync for EMMC {}
+
+impl Command {
+  fn cmdtm(self) -> FieldValue<u32, CMDTM::Register> {
+    let (index, response) = match self {
+      Command::GoIdleState        => (0,  CMDTM::CMD_RSPNS_TYPE::None),
+      Command::AllSendCid         => (2,  CMDTM::CMD_RSPNS_TYPE::Bits136),
+      Command::SendRelativeAddr   => (3,  CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::SelectCard         => (7,  CMDTM::CMD_RSPNS_TYPE::Bits48Busy),
+      Command::SendIfCond         => (8,  CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::SendCsd            => (9,  CMDTM::CMD_RSPNS_TYPE::Bits136),
+      Command::SetBlockLen        => (16, CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::ReadSingleBlock    => (17, CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::ReadMultipleBlock  => (18, CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::WriteBlock         => (24, CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::WriteMultipleBlock => (25, CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::AppCmd             => (55, CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::AppSetBusWidth     => (6,  CMDTM::CMD_RSPNS_TYPE::Bits48),
+      Command::AppSendOpCond      => (41, CMDTM::CMD_RSPNS_TYPE::Bits48),
+    };
+
+    let mut cmdtm = CMDTM::CMD_INDEX.val(index) + response;
+
+    // R2 and R3 responses carry no valid index or CRC
+    if !matches!(self, Command::GoIdleState | Command::AllSendCid | Command::SendCsd | Command::AppSendOpCond) {
+      cmdtm += CMDTM::CMD_IXCHK_EN::SET + CMDTM::CMD_CRCCHK_EN::SET;
+    }
+
+    match self {
+      Command::ReadSingleBlock    => cmdtm + Self::data(Direction::Read),
+      Command::ReadMultipleBlock  => cmdtm + Self::data(Direction::Read) + Self::multi_block(),
+      Command::WriteBlock         => cmdtm + Self::data(Direction::Write),
+      Command::WriteMultipleBlock => cmdtm + Self::data(Direction::Write) + Self::multi_block(),
+      _                           => cmdtm,
+    }
+  }
+
+  fn data(direction: Direction) -> FieldValue<u32, CMDTM::Register> {
+    let dir = match direction {
+      Direction::Read  => CMDTM::TM_DAT_DIR::CardToHost,
+      Direction::Write => CMDTM::TM_DAT_DIR::HostToCard,
+    };
+
+    CMDTM::CMD_ISDATA::SET + dir
+  }
+
+  fn multi_block() -> FieldValue<u32, CMDTM::Register> {
+    CMDTM::TM_MULTI_BLOCK::SET + CMDTM::TM_BLKCNT_EN::SET + CMDTM::TM_AUTO_CMD_EN::Cmd12
+  }
+
+  fn is_busy(self) -> bool {
+    matches!(self, Command::SelectCard)
+  }
+}
+
+impl EMMCInner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  const unsafe fn new(mmio_start_addr: usize, mailbox: &'static Mailbox, clock: Clock) -> Self {
+    Self {
+      registers: unsafe { Registers::new(mmio_start_addr) },
+      mailbox,
+      clock,
+      card: None,
+    }
+  }
+
+  /// Spin until `done` holds; or fail with `error` after `timeout`
+  fn wait_until(&self, timeout: Duration, error: &'static str, done: impl Fn(&Self) -> bool) -> Result<(), &'static str> {
+    let deadline = time::time_manager().uptime() + timeout;
+
+    while !done(self) {
+      if time::time_manager().uptime() > deadline { return Err(error); }
+
+      cpu::nop();
+    }
+
+    Ok(())
+  }
+
+  /// Wait for `flag`; clearing it and failing on any error
+  fn wait_for_interrupt(&mut self, flag: FieldValue<u32, INTERRUPT::Register>) -> Result<(), &'static str> {
+    let mask = flag.mask() | INTERRUPT::ERR::SET.mask();
+
+    self.wait_until(TIMEOUT, "EMMC interrupt timeout", |i| i.registers.INTERRUPT.get() & mask != 0)?;
+
+    let interrupt = self.registers.INTERRUPT.get();
+
+    if interrupt & INTERRUPT::ERR::SET.mask() != 0 {
+      // Clear the errors and get the command and data lines going again
+      self.registers.INTERRUPT.set(interrupt);
+      self.registers.CONTROL1.modify(CONTROL1::SRST_CMD::SET + CONTROL1::SRST_DATA::SET);
+      self.wait_until(TIMEOUT, "EMMC line reset timeout", |i| {
+        !i.registers.CONTROL1.matches_any(&[CONTROL1::SRST_CMD::SET, CONTROL1::SRST_DATA::SET])
+      })?;
+
+      return Err("EMMC command or transfer error");
+    }
+
+    self.registers.INTERRUPT.set(flag.mask());
+
+    Ok(())
+  }
+
+  /// Send `command` and return the first response word
+  fn command(&mut self, command: Command, arg: u32) -> Result<u32, &'static str> {
+    self.wait_until(TIMEOUT, "EMMC command line busy", |i| !i.registers.STATUS.is_set(STATUS::CMD_INHIBIT))?;
+
+    if command.is_busy() {
+      self.wait_until(TIMEOUT, "EMMC data line busy", |i| !i.registers.STATUS.is_set(STATUS::DAT_INHIBIT))?;
+    }
+
+    // Clear stale flags
+    self.registers.INTERRUPT.set(self.registers.INTERRUPT.get());
+
+    self.registers.ARG1.set(arg);
+    self.registers.CMDTM.write(command.cmdtm());
+
+    self.wait_for_interrupt(INTERRUPT::CMD_DONE::SET)?;
+
+    if command.is_busy() { self.wait_for_interrupt(INTERRUPT::DATA_DONE::SET)?; }
+
+    Ok(self.registers.RESP[0].get())
+  }
+
+  /// Send the application-specific `command`
+  fn app_command(&mut self, command: Command, arg: u32) -> Result<u32, &'static str> {
+    let rca = self.card.map_or(0, |c| c.rca);
+
+    self.command(Command::AppCmd, rca << 16)?;
+    self.command(command, arg)
+  }
+
+  /// Set the SD clock to at most `hz`
+  fn set_clock(&mut self, hz: u32) -> Result<(), &'static str> {
+    let base = self.mailbox.clock_rate(self.clock)?;
+
+    // SD clock = base / (2 * divider); or base itself for a divider of 0
+    let mut divider = base.div_ceil(2 * hz);
+
+    // Before spec 3.0 only powers of 2 up to 128 are allowed
+    if self.registers.SLOTISR_VER.read(SLOTISR_VER::SDVERSION) < SDVERSION_3 {
+      divider = divider.next_power_of_two().min(0x80);
+    }
+
+    let divider = divider.min(0x3ff);
+
+    self.registers.CONTROL1.modify(CONTROL1::CLK_EN::CLEAR);
+    self.registers.CONTROL1.modify(
+      CONTROL1::CLK_FREQ8.val(divider & 0xff)
+      +
+      CONTROL1::CLK_FREQ_MS2.val(divider >> 8)
+    );
+
+    self.wait_until(TIMEOUT, "EMMC clock not stable", |i| i.registers.CONTROL1.is_set(CONTROL1::CLK_STABLE))?;
+    self.registers.CONTROL1.modify(CONTROL1::CLK_EN::SET);
+
+    Ok(())
+  }
+
+  fn reset_controller(&mut self) -> Result<(), &'static str> {
+    self.registers.CONTROL0.set(0);
+    self.registers.CONTROL1.write(CONTROL1::SRST_HC::SET);
+    self.wait_until(TIMEOUT, "EMMC reset timeout", |i| !i.registers.CONTROL1.is_set(CONTROL1::SRST_HC))?;
+
+    #[cfg(feature = "bsp_rpi4")]
+    self.registers.CONTROL0.write(CONTROL0::SD_BUS_POWER::V3_3);
+
+    self.registers.CONTROL1.modify(CONTROL1::CLK_INTLEN::SET + CONTROL1::DATA_TOUNIT::Max);
+    self.set_clock(IDENTIFICATION_CLOCK_HZ)?;
+
+    // Report all status flags; but don't raise interrupts
+    self.registers.IRPT_EN.set(0);
+    self.registers.IRPT_MASK.set(u32::MAX);
+
+    Ok(())
+  }
+
+  /// The number of blocks from the CSD; R2 responses lack the CRC byte, so CSD bit n is response bit n - 8
+  fn csd_num_blocks(&self) -> u64 {
+    let resp = [0, 1, 2, 3].map(|i| self.registers.RESP[i].get() as u64);
+    let bits = |high: u32, low: u32| -> u64 {
+      let (high, low) = (high - 8, low - 8);
+      let value = (resp[(low / 32) as usize] | resp.get((low / 32) as usize + 1).map_or(0, |r| r << 32)) >> (low % 32);
+
+      value & ((1 << (high - low + 1)) - 1)
+    };
+
+    match bits(127, 126) {
+      // CSD version 1; capacity = (C_SIZE + 1) * 2^(C_SIZE_MULT + 2) * 2^READ_BL_LEN
+      0 => ((bits(73, 62) + 1) << (bits(49, 47) + 2) << bits(83, 80)) / BLOCK_SIZE as u64,
+
+      // CSD version 2; capacity = (C_SIZE + 1) * 512 KiB
+      _ => (bits(69, 48) + 1) * 1024,
+    }
+  }
+
+  fn init(&mut self) -> Result<(), &'static str> {
+    self.card = None;
+    self.reset_controller()?;
+
+    self.command(Command::GoIdleState, 0)?;
+
+    let version_2 = match self.command(Command::SendIfCond, IF_COND_3V3) {
+      Ok(r) if r & 0xfff == IF_COND_3V3 => true,
+      Ok(_)                             => return Err("SD card does not support 3.3 V"),
+      Err(_)                            => false,
+    };
+
+    let hcs = if version_2 { OCR_HCS } else { 0 };
+    let deadline = time::time_manager().uptime() + POWER_UP_TIMEOUT;
+
+    let ocr = loop {
+      let ocr = self.app_command(Command::AppSendOpCond, OCR_VOLTAGE_WINDOW | hcs)?;
+
+      if ocr & OCR_BUSY != 0 { break ocr; }
+      if time::time_manager().uptime() > deadline { return Err("SD card did not power up"); }
+
+      time::time_manager().spin_for(Duration::from_millis(10));
+    };
+
+    self.command(Command::AllSendCid, 0)?;
+
+    let rca = self.command(Command::SendRelativeAddr, 0)? >> 16;
+
+    self.command(Command::SendCsd, rca << 16)?;
+
+    let card = Card {
+      rca,
+      block_addressing: ocr & OCR_HCS != 0,
+      num_blocks: self.csd_num_blocks(),
+    };
+
+    self.set_clock(DEFAULT_SPEED_CLOCK_HZ)?;
+    self.command(Command::SelectCard, rca << 16)?;
+    self.card = Some(card);
+
+    self.app_command(Command::AppSetBusWidth, BUS_WIDTH_4)?;
+    self.registers.CONTROL0.modify(CONTROL0::HCTL_DWIDTH::FourBit);
+
+    if !card.block_addressing { self.command(Command::SetBlockLen, BLOCK_SIZE as u32)?; }
+
+    Ok(())
+  }
+
+  /// Move `len` bytes starting at block `lba` between the card and `transfer_block`; which is called once per block
+  fn transfer(
+    &mut self,
+    direction: Direction,
+    lba: u64,
+    len: usize,
+    mut transfer_block: impl FnMut(&mut Self, usize),
+  ) -> Result<(), &'static str> {
+    let card = self.card.ok_or("No SD card")?;
+
+    block::check_range(lba, len, card.num_blocks)?;
+
+    let num_blocks = len / BLOCK_SIZE;
+
+    if num_blocks == 0 { return Ok(()); }
+
+    let address = if card.block_addressing { lba } else { lba * BLOCK_SIZE as u64 };
+    let address = u32::try_from(address).map_err(|_| "Block address out of range")?;
+
+    let command = match (direction, num_blocks) {
+      (Direction::Read, 1)  => Command::ReadSingleBlock,
+      (Direction::Read, _)  => Command::ReadMultipleBlock,
+      (Direction::Write, 1) => Command::WriteBlock,
+      (Direction::Write, _) => Command::WriteMultipleBlock,
+    };
+
+    let ready = match direction {
+      Direction::Read  => INTERRUPT::READ_RDY::SET,
+      Direction::Write => INTERRUPT::WRITE_RDY::SET,
+    };
+
+    self.wait_until(TIMEOUT, "EMMC data line busy", |i| !i.registers.STATUS.is_set(STATUS::DAT_INHIBIT))?;
+    self.registers.BLKSIZECNT.write(BLKSIZECNT::BLKCNT.val(num_blocks as u32) + BLKSIZECNT::BLKSIZE.val(BLOCK_SIZE as u32));
+    self.command(command, address)?;
+
+    for block in 0..num_blocks {
+      self.wait_for_interrupt(ready)?;
+      transfer_block(self, block);
+    }
+
+    self.wait_for_interrupt(INTERRUPT::DATA_DONE::SET)
+  }
+
+  fn read_blocks(&mut self, lba: u64, buf: &mut [u8]) -> Result<(), &'static str> {
+    self.transfer(Direction::Read, lba, buf.len(), |i, block| {
+      for word in buf[block * BLOCK_SIZE..(block + 1) * BLOCK_SIZE].chunks_exact_mut(4) {
+        word.copy_from_slice(&i.registers.DATA.get().to_le_bytes());
+      }
+    })
+  }
+
+  fn write_blocks(&mut self, lba: u64, buf: &[u8]) -> Result<(), &'static str> {
+    self.transfer(Direction::Write, lba, buf.len(), |i, block| {
+      for word in buf[block * BLOCK_SIZE..(block + 1) * BLOCK_SIZE].chunks_exact(4) {
+        i.registers.DATA.set(u32::from_le_bytes([word[0], word[1], word[2], word[3]]));
+      }
+    })
+  }
+}
+
+impl EMMC {
+  pub const COMPATIBLE: &'static str = "BCM EMMC (Arasan SDHCI)";
+
+  /// Create an instance; the base clock rate of `clock` is queried through `mailbox`
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  pub const unsafe fn new(mmio_start_addr: usize, mailbox: &'static Mailbox, clock: Clock) -> Self {
+    Self {
+      inner: NullLock::new(unsafe { EMMCInner::new(mmio_start_addr, mailbox, clock) }),
+    }
+  }
+
+  /// Whether init found a card
+  pub fn has_card(&self) -> bool {
+    self.inner.lock(|i| i.card.is_some())
+  }
+}
+
+impl driver::interface::DeviceDriver for EMMC {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+
+  /// A board without a card still boots; the driver just has no card
+  unsafe fn init(&self) -> Result<(), &'static str> {
+    if let Err(e) = self.inner.lock(|i| i.init()) { crate::warn!("No SD card: {}", e); }
+
+    Ok(())
+  }
+}
+
+impl block::interface::BlockDevice for EMMC {
+  fn block_size(&self) -> usize {
+    BLOCK_SIZE
+  }
+
+  fn num_blocks(&self) -> u64 {
+    self.inner.lock(|i| i.card.map_or(0, |c| c.num_blocks))
+  }
+
+  fn read_blocks(&self, lba: u64, buf: &mut [u8]) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.read_blocks(lba, buf))
+  }
+
+  fn write_blocks(&self, lba: u64, buf: &[u8]) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.write_blocks(lba, buf))
+  }
+}
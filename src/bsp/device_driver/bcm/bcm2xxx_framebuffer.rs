@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Framebuffer driver with a text console
+//!
+//! The firmware allocates the framebuffer through the mailbox; text is rendered into it with a bitmap font
+//! The framebuffer must lie in memory that is mapped non-cacheable; so writes reach the display without cache maintenance
+
+use core::{
+  fmt,
+  ops::RangeInclusive,
+  ptr,
+};
+
+use crate::{
+  bsp::device_driver::{
+    FramebufferInfo,
+    Mailbox,
+  },
+  console::{
+    self,
+    font,
+  },
+  driver,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+  },
+  warn,
+};
+
+/// Only 32 bits per pixel are supported
+const DEPTH: u32 = 32;
+
+/// Grey; the same in RGB and BGR pixel order
+const FOREGROUND: u32 = 0x00AA_AAAA;
+const BACKGROUND: u32 = 0x0000_0000;
+
+const TAB_WIDTH: usize = 8;
+
+struct FramebufferInner {
+  mailbox: &'static Mailbox,
+  width: u32,
+  height: u32,
+
+  /// Where the framebuffer may be placed
+  mapped_range: fn() -> RangeInclusive<usize>,
+
+  /// `None` until the firmware allocated the framebuffer
+  info: Option<FramebufferInfo>,
+
+  col: usize,
+  row: usize,
+}
+
+/// Representation of the framebuffer
+pub struct Framebuffer {
+  inner: IRQSafeNullLock<FramebufferInner>,
+}
+
+unsafe impl Sync for Framebuffer {}
+
+impl FramebufferInner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - `mapped_range` must return a range that is mapped non-cacheable
+  const unsafe fn new(mailbox: &'static Mailbox, width: u32, height: u32, mapped_range: fn() -> RangeInclusive<usize>) -> Self {
+    Self {
+      mailbox,
+      width,
+      height,
+      mapped_range,
+      info: None,
+      col: 0,
+      row: 0,
+    }
+  }
+
+  fn init(&mut self) -> Result<(), &'static str> {
+    let info = self.mailbox.allocate_framebuffer(self.width, self.height, DEPTH)?;
+
+    if info.depth != DEPTH { return Err("Firmware did not set up 32 bits per pixel"); }
+
+    let mapped_range = (self.mapped_range)();
+
+    if !mapped_range.contains(&info.base) || !mapped_range.contains(&(info.base + info.size - 1)) {
+      return Err("Framebuffer lies outside of the non-cacheable region");
+    }
+
+    self.info = Some(info);
+    self.col = 0;
+    self.row = 0;
+    self.fill_lines(0, info.height as usize);
+
+    Ok(())
+  }
+
+  /// The text grid's size in characters
+  fn text_size(info: &FramebufferInfo) -> (usize, usize) {
+    (info.width as usize / font::WIDTH, info.height as usize / font::HEIGHT)
+  }
+
+  /// Fill pixel lines `from` up to `to` with the background color
+  fn fill_lines(&mut self, from: usize, to: usize) {
+    let Some(info) = self.info else { return; };
+
+    for y in from..to {
+      for x in 0..info.width as usize {
+        self.put_pixel(&info, x, y, BACKGROUND);
+      }
+    }
+  }
+
+  fn put_pixel(&self, info: &FramebufferInfo, x: usize, y: usize, color: u32) {
+    let offset = y * info.pitch as usize + x * 4;
+
+    unsafe { ptr::write_volatile((info.base + offset) as *mut u32, color) };
+  }
+
+  fn draw_glyph(&self, info: &FramebufferInfo, c: char) {
+    let x0 = self.col * font::WIDTH;
+    let y0 = self.row * font::HEIGHT;
+
+    for (dy, bits) in font::glyph(c).iter().enumerate() {
+      for dx in 0..font::WIDTH {
+        let color = if bits & (1 << dx) != 0 { FOREGROUND } else { BACKGROUND };
+
+        self.put_pixel(info, x0 + dx, y0 + dy, color);
+      }
+    }
+  }
+
+  /// Move the text up by one line and clear the last one
+  fn scroll(&mut self) {
+    let Some(info) = self.info else { return; };
+
+    let (_, rows) = Self::text_size(&info);
+    let line_bytes = font::HEIGHT * info.pitch as usize;
+
+    unsafe { ptr::copy((info.base + line_bytes) as *const u8, info.base as *mut u8, (rows - 1) * line_bytes) };
+
+    self.fill_lines((rows - 1) * font::HEIGHT, rows * font::HEIGHT);
+  }
+
+  fn new_line(&mut self) {
+    let Some(info) = self.info else { return; };
+
+    let (_, rows) = Self::text_size(&info);
+
+    self.col = 0;
+
+    if self.row + 1 < rows {
+      self.row += 1;
+    } else {
+      self.scroll();
+    }
+  }
+
+  fn write_char(&mut self, c: char) {
+    let Some(info) = self.info else { return; };
+
+    let (cols, _) = Self::text_size(&info);
+
+    match c {
+      '\n'   => self.new_line(),
+      '\r'   => self.col = 0,
+      '\t'   => self.col = (self.col / TAB_WIDTH + 1) * TAB_WIDTH,
+      '\x08' => self.col = self.col.saturating_sub(1),
+      c      => {
+        self.draw_glyph(&info, c);
+        self.col += 1;
+      },
+    }
+
+    if self.col >= cols { self.new_line(); }
+  }
+}
+
+impl fmt::Write for FramebufferInner {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for c in s.chars() { self.write_char(c); }
+
+    Ok(())
+  }
+}
+
+impl Framebuffer {
+  pub const COMPATIBLE: &'static str = "BCM Framebuffer";
+
+  /// Create an instance; the framebuffer is allocated through `mailbox` during init
+  ///
+  /// # Safety
+  ///
+  /// - `mapped_range` must return a range that is mapped non-cacheable
+  pub const unsafe fn new(mailbox: &'static Mailbox, width: u32, height: u32, mapped_range: fn() -> RangeInclusive<usize>) -> Self {
+    Self {
+      inner: IRQSafeNullLock::new(unsafe { FramebufferInner::new(mailbox, width, height, mapped_range) }),
+    }
+  }
+
+  /// Whether the firmware allocated the framebuffer
+  pub fn is_active(&self) -> bool {
+    self.inner.lock(|i| i.info.is_some())
+  }
+}
+
+impl driver::interface::DeviceDriver for Framebuffer {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+
+  /// A board without a display still boots; the framebuffer just stays inactive
+  unsafe fn init(&self) -> Result<(), &'static str> {
+    if let Err(e) = self.inner.lock(|i| i.init()) { warn!("No framebuffer: {}", e); }
+
+    Ok(())
+  }
+}
+
+impl console::interface::Write for Framebuffer {
+  fn write_char(&self, c: char) {
+    self.inner.lock(|i| i.write_char(c));
+  }
+
+  fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result {
+    self.inner.lock(|i| fmt::Write::write_fmt(i, args))
+  }
+
+  fn flush(&self) {}
+}
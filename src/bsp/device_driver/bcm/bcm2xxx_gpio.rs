@@ -3,49 +3,48 @@
 // Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
 
 //! GPIO driver
+//!
+//! Pins are claimed by name before use; so two drivers can't end up fighting over the same pin
+//! Changing a pin's function, pull, output or events takes the owner's name too, and fails for anyone else; reading a pin's level doesn't
+//!
+//! Edge and level events raise the GPIO IRQ; the event callback registered for the pin is then called in IRQ context
+//! Level events stay asserted for as long as the level holds; their callback has to disable the event or remove its cause
 
-use core::time::Duration;
+#[cfg(feature = "bsp_rpi3")]
+use core::{
+  ops::RangeInclusive,
+  time::Duration,
+};
 
 use tock_registers::{
   interfaces::{
-    ReadWriteable,
+    Readable,
     Writeable,
   },
   register_bitfields,
   register_structs,
-  registers::ReadWrite,
+  registers::{
+    ReadOnly,
+    ReadWrite,
+    WriteOnly,
+  },
 };
 
+#[cfg(feature = "bsp_rpi3")]
+use crate::time;
 use crate::{
   bsp::device_driver::common::MMIODerefWrapper,
   driver,
+  exception,
   synchronization::{
-    NullLock,
+    IRQSafeNullLock,
     self,
   },
-  time,
 };
 
 register_bitfields! {
   u32,
 
-  /// GPIO Function Select 1
-  GPFSEL1 [
-    /// Pin 15
-    FSEL15 OFFSET(15) NUMBITS(3) [
-      Input    = 0b000,
-      Output   = 0b001,
-      AltFunc0 = 0b100 // PL011 UART RX
-    ],
-
-    /// Pin 14
-    FSEL14 OFFSET(12) NUMBITS(3) [
-      Input    = 0b000,
-      Output   = 0b001,
-      AltFunc0 = 0b100 // PL011 UART TX
-    ]
-  ],
-
   /// GPIO Pull-up/down Register
   /// BCM2837 only
   GPPUD [
@@ -55,127 +54,341 @@ register_bitfields! {
       PullDown = 0b01,
       PullUp   = 0b10
     ]
-  ],
-
-  /// GPIO Pull-up/down Clock Register 0
-  /// BCM2837 only
-  GPPUDCLK0 [
-    /// Pin 15
-    PUDCLK15 OFFSET(15) NUMBITS(1) [
-      NoEffect    = 0,
-      AssertClock = 1
-    ],
-
-    /// Pin 14
-    PUDCLK14 OFFSET(14) NUMBITS(1) [
-      NoEffect    = 0,
-      AssertClock = 1
-    ]
-  ],
-
-  /// GPIO Pull-up / Pull-down Register 0
-  /// BCM2711 only
-  GPIO_PUP_PDN_CNTRL_REG0 [
-    /// Pin 15
-    GPIO_PUP_PDN_CNTRL15 OFFSET(30) NUMBITS(2) [
-      NoResistor = 0b00,
-      PullUp     = 0b01
-    ],
-
-    /// Pin 14
-    GPIO_PUP_PDN_CNTRL14 OFFSET(28) NUMBITS(2) [
-      NoResistor = 0b00,
-      PullUp     = 0b01
-    ]
   ]
 }
 
 register_structs! {
   #[allow(non_snake_case)]
   RegisterBlock {
-    (0x00 => _reserved1),
-    (0x04 => GPFSEL1: ReadWrite<u32, GPFSEL1::Register>),
-    (0x08 => _reserved2),
+    /// Function select; 3 bits per pin, 10 pins per register
+    (0x00 => GPFSEL: [ReadWrite<u32>; 6]),
+    (0x18 => _reserved1),
+    /// Output set; 1 bit per pin, writing 0 has no effect
+    (0x1c => GPSET: [WriteOnly<u32>; 2]),
+    (0x24 => _reserved2),
+    /// Output clear; 1 bit per pin, writing 0 has no effect
+    (0x28 => GPCLR: [WriteOnly<u32>; 2]),
+    (0x30 => _reserved3),
+    /// Pin level; 1 bit per pin
+    (0x34 => GPLEV: [ReadOnly<u32>; 2]),
+    (0x3c => _reserved4),
+    /// Event detect status; 1 bit per pin, writing 1 clears the event
+    (0x40 => GPEDS: [ReadWrite<u32>; 2]),
+    (0x48 => _reserved5),
+    /// Rising edge detect enable; 1 bit per pin
+    (0x4c => GPREN: [ReadWrite<u32>; 2]),
+    (0x54 => _reserved6),
+    /// Falling edge detect enable; 1 bit per pin
+    (0x58 => GPFEN: [ReadWrite<u32>; 2]),
+    (0x60 => _reserved7),
+    /// High level detect enable; 1 bit per pin
+    (0x64 => GPHEN: [ReadWrite<u32>; 2]),
+    (0x6c => _reserved8),
+    /// Low level detect enable; 1 bit per pin
+    (0x70 => GPLEN: [ReadWrite<u32>; 2]),
+    (0x78 => _reserved9),
     (0x94 => GPPUD: ReadWrite<u32, GPPUD::Register>),
-    (0x98 => GPPUDCLK0: ReadWrite<u32, GPPUDCLK0::Register>),
-    (0x9c => _reserved3),
-    (0xe4 => GPIO_PUP_PDN_CNTRL_REG0: ReadWrite<u32, GPIO_PUP_PDN_CNTRL_REG0::Register>),
-    (0xe8 => @END),
+    /// Pull-up/down clock; 1 bit per pin
+    /// BCM2837 only
+    (0x98 => GPPUDCLK: [ReadWrite<u32>; 2]),
+    (0xa0 => _reserved10),
+    /// Pull-up/down control; 2 bits per pin, 16 pins per register
+    /// BCM2711 only
+    (0xe4 => GPIO_PUP_PDN_CNTRL_REG: [ReadWrite<u32>; 4]),
+    (0xf4 => @END),
   }
 }
 
 /// Abstraction for the associated MMIO registers
 type Registers = MMIODerefWrapper<RegisterBlock>;
 
+/// The number of GPIO pins
+#[cfg(feature = "bsp_rpi3")]
+pub const NUM_PINS: usize = 54;
+
+/// The number of GPIO pins
+#[cfg(feature = "bsp_rpi4")]
+pub const NUM_PINS: usize = 58;
+
+/// Pin functions; the values are the function select encodings
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Function {
+  Input  = 0b000,
+  Output = 0b001,
+  Alt0   = 0b100,
+  Alt1   = 0b101,
+  Alt2   = 0b110,
+  Alt3   = 0b111,
+  Alt4   = 0b011,
+  Alt5   = 0b010,
+}
+
+/// Pin pull-up/down resistor settings
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Pull {
+  None,
+  Down,
+  Up,
+}
+
+/// Pin events that can raise the GPIO IRQ
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Event {
+  RisingEdge,
+  FallingEdge,
+  High,
+  Low,
+}
+
+/// Called in IRQ context with the number of the pin an event was detected on
+pub type EventCallback = fn(pin: usize);
+
+/// The resistor setting for the UART pins
+#[cfg(feature = "bsp_rpi3")]
+const UART_PULL: Pull = Pull::None;
+
+/// The resistor setting for the UART pins
+#[cfg(feature = "bsp_rpi4")]
+const UART_PULL: Pull = Pull::Up;
+
+/// The SD card pins; CLK, CMD and DAT0 to DAT3
+///
+/// On the BCM2711 the card slot is wired to EMMC2, which has dedicated pins
+#[cfg(feature = "bsp_rpi3")]
+const EMMC_PINS: RangeInclusive<usize> = 48..=53;
+
 struct GPIOInner {
   registers: Registers,
+  owners: [Option<&'static str>; NUM_PINS],
+  callbacks: [Option<EventCallback>; NUM_PINS],
 }
 
 /// Representation of the GPIO hardware
 pub struct GPIO {
-  inner: NullLock<GPIOInner>,
+  inner: IRQSafeNullLock<GPIOInner>,
 }
 
 unsafe impl Sync for GPIO {}
 
+/// Fail for pins that don't exist
+fn check_pin(pin: usize) -> Result<(), &'static str> {
+  if pin >= NUM_PINS { return Err("No such GPIO pin"); }
+
+  Ok(())
+}
+
 impl GPIOInner {
   /// Create an instance
-  /// 
+  ///
   /// # Safety
   /// - The caller must ensure a valid MMIO start address is specified
   pub const unsafe fn new(mmio_start_addr: usize) -> Self {
     Self {
       registers: unsafe { Registers::new(mmio_start_addr) },
+      owners: [None; NUM_PINS],
+      callbacks: [None; NUM_PINS],
+    }
+  }
+
+  /// Reserve `pin` for `owner`
+  fn claim(&mut self, pin: usize, owner: &'static str) -> Result<(), &'static str> {
+    check_pin(pin)?;
+
+    match self.owners[pin] {
+      Some(_) => Err("GPIO pin already claimed"),
+      None    => {
+        self.owners[pin] = Some(owner);
+
+        Ok(())
+      }
+    }
+  }
+
+  /// Fail unless `pin` exists and is claimed by `owner`
+  fn check_owner(&self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    check_pin(pin)?;
+
+    match self.owners[pin] {
+      Some(o) if o == owner => Ok(()),
+      _                     => Err("GPIO pin not claimed by this owner"),
     }
   }
 
-  /// Disable pull-up/down on lins 14 and 15
+  /// Give `pin` back; only its owner may do so
+  fn release(&mut self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.check_owner(pin, owner)?;
+
+    self.owners[pin] = None;
+
+    Ok(())
+  }
+
+  fn set_function(&mut self, pin: usize, owner: &str, function: Function) -> Result<(), &'static str> {
+    self.check_owner(pin, owner)?;
+
+    let (reg, shift) = (pin / 10, (pin % 10) * 3);
+    let value = self.registers.GPFSEL[reg].get();
+
+    self.registers.GPFSEL[reg].set((value & !(0b111 << shift)) | ((function as u32) << shift));
+
+    Ok(())
+  }
+
+  /// The BCM2837 latches the pull setting into each pin through a clocked sequence
   #[cfg(feature = "bsp_rpi3")]
-  fn disable_pud_14_15_bcm2837(&mut self) {
-    // The Linu x2837 GPIO driver waits 1 µs between steps
+  fn set_pull(&mut self, pin: usize, owner: &str, pull: Pull) -> Result<(), &'static str> {
+    // The Linux 2837 GPIO driver waits 1 µs between steps
     const DELAY: Duration = Duration::from_micros(1);
 
-    self.registers.GPPUD.write(GPPUD::PUD::Off);
+    self.check_owner(pin, owner)?;
+
+    let pud = match pull {
+      Pull::None => GPPUD::PUD::Off,
+      Pull::Down => GPPUD::PUD::PullDown,
+      Pull::Up   => GPPUD::PUD::PullUp,
+    };
+
+    self.registers.GPPUD.write(pud);
     time::time_manager().spin_for(DELAY);
 
-    self.registers.GPPUDCLK0.write(
-      GPPUDCLK0::PUDCLK15::AssertClock
-      +
-      GPPUDCLK0::PUDCLK14::AssertClock
-    );
+    self.registers.GPPUDCLK[pin / 32].set(1 << (pin % 32));
     time::time_manager().spin_for(DELAY);
 
     self.registers.GPPUD.write(GPPUD::PUD::Off);
-    self.registers.GPPUDCLK0.set(0);
+    self.registers.GPPUDCLK[pin / 32].set(0);
+
+    Ok(())
   }
 
-  /// Disable pull-up/down on pins 14 and 15
+  /// The BCM2711 has a directly writable pull setting per pin
   #[cfg(feature = "bsp_rpi4")]
-  fn disable_pud_14_15_bcm2711(&mut self) {
-    self.registers.GPIO_PUP_PDN_CNTRL_REG0.write(
-      GPIO_PUP_PDN_CNTRL_REG0::GPIO_PUP_PDN_CNTRL15::PullUp
-      +
-      GPIO_PUP_PDN_CNTRL_REG0::GPIO_PUP_PDN_CNTRL14::PullUp
-    );
+  fn set_pull(&mut self, pin: usize, owner: &str, pull: Pull) -> Result<(), &'static str> {
+    self.check_owner(pin, owner)?;
+
+    let bits = match pull {
+      Pull::None => 0b00,
+      Pull::Up   => 0b01,
+      Pull::Down => 0b10,
+    };
+
+    let (reg, shift) = (pin / 16, (pin % 16) * 2);
+    let value = self.registers.GPIO_PUP_PDN_CNTRL_REG[reg].get();
+
+    self.registers.GPIO_PUP_PDN_CNTRL_REG[reg].set((value & !(0b11 << shift)) | (bits << shift));
+
+    Ok(())
+  }
+
+  fn set(&mut self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.check_owner(pin, owner)?;
+
+    self.registers.GPSET[pin / 32].set(1 << (pin % 32));
+
+    Ok(())
+  }
+
+  fn clear(&mut self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.check_owner(pin, owner)?;
+
+    self.registers.GPCLR[pin / 32].set(1 << (pin % 32));
+
+    Ok(())
+  }
+
+  fn read(&self, pin: usize) -> Result<bool, &'static str> {
+    check_pin(pin)?;
+
+    Ok(self.registers.GPLEV[pin / 32].get() & (1 << (pin % 32)) != 0)
+  }
+
+  /// The detect enable registers for `event`
+  fn event_registers(&self, event: Event) -> &[ReadWrite<u32>; 2] {
+    match event {
+      Event::RisingEdge  => &self.registers.GPREN,
+      Event::FallingEdge => &self.registers.GPFEN,
+      Event::High        => &self.registers.GPHEN,
+      Event::Low         => &self.registers.GPLEN,
+    }
   }
 
-  /// Map PL011 UART as standard output
-  /// 
+  fn enable_event(&mut self, pin: usize, owner: &str, event: Event, callback: EventCallback) -> Result<(), &'static str> {
+    self.check_owner(pin, owner)?;
+
+    if matches!(self.callbacks[pin], Some(c) if c as usize != callback as usize) {
+      return Err("GPIO pin already has a different event callback");
+    }
+
+    self.callbacks[pin] = Some(callback);
+
+    // Don't report an event that was latched before the callback existed
+    self.registers.GPEDS[pin / 32].set(1 << (pin % 32));
+
+    let reg = &self.event_registers(event)[pin / 32];
+    reg.set(reg.get() | (1 << (pin % 32)));
+
+    Ok(())
+  }
+
+  fn disable_events(&mut self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.check_owner(pin, owner)?;
+
+    for event in [Event::RisingEdge, Event::FallingEdge, Event::High, Event::Low] {
+      let reg = &self.event_registers(event)[pin / 32];
+      reg.set(reg.get() & !(1 << (pin % 32)));
+    }
+
+    self.registers.GPEDS[pin / 32].set(1 << (pin % 32));
+    self.callbacks[pin] = None;
+
+    Ok(())
+  }
+
+  /// Clear and return the detected events of `bank`; bit n is set for pin `32 * bank + n`
+  fn take_events(&mut self, bank: usize) -> u32 {
+    let events = self.registers.GPEDS[bank].get();
+    self.registers.GPEDS[bank].set(events);
+
+    events
+  }
+
+  /// Claim pins 14 and 15 for `owner` and switch them to `function`
+  ///
   /// USB TX -> pin 14 (Pi RX)
   /// USB RX -> pin 15 (Pi TX)
-  pub fn map_pl011_uart(&mut self) {
-    // Select the function for the pins
-    self.registers.GPFSEL1.modify(
-      GPFSEL1::FSEL15::AltFunc0
-      +
-      GPFSEL1::FSEL14::AltFunc0
-    );
-
-    // Disable pul-up/down on pins 14 and 15
-    #[cfg(feature = "bsp_rpi3")]
-    self.disable_pud_14_15_bcm2837();
-    #[cfg(feature = "bsp_rpi4")]
-    self.disable_pud_14_15_bcm2711();
+  fn map_uart(&mut self, owner: &'static str, function: Function) -> Result<(), &'static str> {
+    self.claim(14, owner)?;
+
+    if let Err(e) = self.claim(15, owner) {
+      self.release(14, owner)?;
+
+      return Err(e);
+    }
+
+    for pin in [14, 15] {
+      self.set_function(pin, owner, function)?;
+      self.set_pull(pin, owner, UART_PULL)?;
+    }
+
+    Ok(())
+  }
+
+  /// Claim pins 48 to 53 for `owner` and switch them to the EMMC controller
+  #[cfg(feature = "bsp_rpi3")]
+  fn map_emmc(&mut self, owner: &'static str) -> Result<(), &'static str> {
+    for pin in EMMC_PINS {
+      if let Err(e) = self.claim(pin, owner) {
+        for claimed in *EMMC_PINS.start()..pin { self.release(claimed, owner)?; }
+
+        return Err(e);
+      }
+    }
+
+    for pin in EMMC_PINS {
+      self.set_function(pin, owner, Function::Alt3)?;
+      self.set_pull(pin, owner, Pull::Up)?;
+    }
+
+    Ok(())
   }
 }
 
@@ -183,18 +396,79 @@ impl GPIO {
   pub const COMPATIBLE: &'static str = "BCM GPIO";
 
   /// Create and instance
-  /// 
+  ///
   /// # Safety
   /// - The caller must provide a valid MMIO start address
   pub const unsafe fn new(mmio_start_addr: usize) -> Self {
     Self {
-      inner: NullLock::new(unsafe { GPIOInner::new(mmio_start_addr) }),
+      inner: IRQSafeNullLock::new(unsafe { GPIOInner::new(mmio_start_addr) }),
     }
   }
 
-  /// Concurrency-safe version of `GPIOInner.map_pl011_uart()`
-  pub fn map_pl011_uart(&self) {
-    self.inner.lock(|i| i.map_pl011_uart())
+  /// Reserve `pin` for `owner`; fails if it is already claimed
+  pub fn claim(&self, pin: usize, owner: &'static str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.claim(pin, owner))
+  }
+
+  /// Give `pin` back; fails unless it is claimed by `owner`
+  pub fn release(&self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.release(pin, owner))
+  }
+
+  /// The owner of `pin`; if it is claimed
+  pub fn owner(&self, pin: usize) -> Option<&'static str> {
+    self.inner.lock(|i| i.owners.get(pin).copied().flatten())
+  }
+
+  /// Select the function of `pin`; which `owner` must have claimed
+  pub fn set_function(&self, pin: usize, owner: &str, function: Function) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.set_function(pin, owner, function))
+  }
+
+  /// Select the pull-up/down resistor setting of `pin`; which `owner` must have claimed
+  pub fn set_pull(&self, pin: usize, owner: &str, pull: Pull) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.set_pull(pin, owner, pull))
+  }
+
+  /// Drive output `pin` high; `owner` must have claimed it
+  pub fn set(&self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.set(pin, owner))
+  }
+
+  /// Drive output `pin` low; `owner` must have claimed it
+  pub fn clear(&self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.clear(pin, owner))
+  }
+
+  /// The level of `pin`; `true` if high, whoever claimed it
+  pub fn read(&self, pin: usize) -> Result<bool, &'static str> {
+    self.inner.lock(|i| i.read(pin))
+  }
+
+  /// Call `callback` when `event` is detected on `pin`, which `owner` must have claimed; a pin can have several events enabled, but only one callback
+  pub fn enable_event(&self, pin: usize, owner: &str, event: Event, callback: EventCallback) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.enable_event(pin, owner, event, callback))
+  }
+
+  /// Disable all events of `pin` and remove its callback; `owner` must have claimed it
+  pub fn disable_events(&self, pin: usize, owner: &str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.disable_events(pin, owner))
+  }
+
+  /// Map the PL011 UART to pins 14 and 15; claiming them for `owner`
+  pub fn map_pl011_uart(&self, owner: &'static str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.map_uart(owner, Function::Alt0))
+  }
+
+  /// Map the mini UART to pins 14 and 15; claiming them for `owner`
+  pub fn map_mini_uart(&self, owner: &'static str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.map_uart(owner, Function::Alt5))
+  }
+
+  /// Map the EMMC controller to pins 48 to 53; claiming them for `owner`
+  #[cfg(feature = "bsp_rpi3")]
+  pub fn map_emmc(&self, owner: &'static str) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.map_emmc(owner))
   }
 }
 
@@ -203,3 +477,21 @@ use synchronization::interface::Mutex;
 impl driver::interface::DeviceDriver for GPIO {
   fn compatible(&self) -> &'static str { Self::COMPATIBLE }
 }
+
+impl exception::asynchronous::interface::IRQHandler for GPIO {
+  fn handle(&self) -> Result<(), &'static str> {
+    for bank in 0..NUM_PINS.div_ceil(32) {
+      let mut events = self.inner.lock(|i| i.take_events(bank));
+
+      while events != 0 {
+        let pin = bank * 32 + events.trailing_zeros() as usize;
+        events &= events - 1;
+
+        // The lock is not held while the callback runs; so it can use the GPIO itself
+        if let Some(callback) = self.inner.lock(|i| i.callbacks[pin]) { callback(pin); }
+      }
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Interrupt controller driver
+//!
+//! The BCM2837 has two of them:
+//! - The local interrupt controller; one per core, for the core timers and mailboxes
+//! - The peripheral interrupt controller; for the GPU and ARM peripherals (UARTs, GPIO, ...)
+//!
+//! Of the local interrupt controller, only the core timer IRQs of core 0 are supported so far
+//! Local IRQ n is bit n of the core's IRQ source register; the peripheral IRQs are signaled to core 0 as local IRQ 8
+
+use core::fmt;
+
+use tock_registers::{
+  interfaces::{
+    Readable,
+    Writeable,
+  },
+  register_structs,
+  registers::{
+    ReadOnly,
+    ReadWrite,
+    WriteOnly,
+  },
+};
+
+use crate::{
+  bsp::device_driver::common::MMIODerefWrapper,
+  common::BoundedUsize,
+  driver,
+  exception::asynchronous::{
+    interface,
+    IRQHandlerDescriptor,
+  },
+  info,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+  },
+};
+
+register_structs! {
+  #[allow(non_snake_case)]
+  LocalRegisterBlock {
+    (0x00 => _reserved1),
+    /// Route the core timer IRQs of core 0 to its IRQ line; bit n for local IRQ n
+    (0x40 => CORE0_TIMER_IRQCNTL: ReadWrite<u32>),
+    (0x44 => _reserved2),
+    /// Pending IRQs of core 0; bit n for local IRQ n
+    (0x60 => CORE0_IRQ_SOURCE: ReadOnly<u32>),
+    (0x64 => @END),
+  }
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  RegisterBlock {
+    (0x00 => _reserved1),
+    /// Pending state of peripheral IRQs 0 to 31
+    (0x04 => PENDING_1: ReadOnly<u32>),
+    /// Pending state of peripheral IRQs 32 to 63
+    (0x08 => PENDING_2: ReadOnly<u32>),
+    (0x0c => _reserved2),
+    /// Enable peripheral IRQs 0 to 31; writing 0 has no effect
+    (0x10 => ENABLE_1: WriteOnly<u32>),
+    /// Enable peripheral IRQs 32 to 63; writing 0 has no effect
+    (0x14 => ENABLE_2: WriteOnly<u32>),
+    (0x18 => _reserved3),
+    /// Disable peripheral IRQs 0 to 31; writing 0 has no effect
+    (0x1c => DISABLE_1: WriteOnly<u32>),
+    /// Disable peripheral IRQs 32 to 63; writing 0 has no effect
+    (0x20 => DISABLE_2: WriteOnly<u32>),
+    (0x24 => @END),
+  }
+}
+
+/// Abstraction for the local interrupt controller's MMIO registers
+type LocalRegisters = MMIODerefWrapper<LocalRegisterBlock>;
+
+/// Abstraction for the associated MMIO registers
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// A local (per core) IRQ
+pub type LocalIRQ = BoundedUsize<{ InterruptController::MAX_LOCAL_IRQ_NUMBER }>;
+
+/// A peripheral IRQ
+pub type PeripheralIRQ = BoundedUsize<{ InterruptController::MAX_PERIPHERAL_IRQ_NUMBER }>;
+
+/// The BCM2837 IRQ number type
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub enum IRQNumber {
+  Local(LocalIRQ),
+  Peripheral(PeripheralIRQ),
+}
+
+const NUM_LOCAL_IRQS:      usize = InterruptController::MAX_LOCAL_IRQ_NUMBER + 1;
+const NUM_PERIPHERAL_IRQS: usize = InterruptController::MAX_PERIPHERAL_IRQ_NUMBER + 1;
+
+/// The local IRQs 0 to 3; `CNTPSIRQ`, `CNTPNSIRQ`, `CNTHPIRQ` and `CNTVIRQ`
+const MAX_CORE_TIMER_IRQ_NUMBER: usize = 3;
+
+struct InterruptControllerInner {
+  local_registers: LocalRegisters,
+  registers: Registers,
+  local_handler_table: [Option<IRQHandlerDescriptor<IRQNumber>>; NUM_LOCAL_IRQS],
+  handler_table: [Option<IRQHandlerDescriptor<IRQNumber>>; NUM_PERIPHERAL_IRQS],
+
+  /// Bit n is set if local IRQ n is enabled
+  local_enabled: u32,
+
+  /// Bit n is set if peripheral IRQ n is enabled
+  enabled: u64,
+}
+
+/// Representation of the interrupt controller
+pub struct InterruptController {
+  inner: IRQSafeNullLock<InterruptControllerInner>,
+}
+
+unsafe impl Sync for InterruptController {}
+
+impl fmt::Display for IRQNumber {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      IRQNumber::Local(number)      => write!(f, "Local({})", number),
+      IRQNumber::Peripheral(number) => write!(f, "Peripheral({})", number),
+    }
+  }
+}
+
+impl InterruptControllerInner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide valid MMIO start addresses
+  const unsafe fn new(local_mmio_start_addr: usize, periph_mmio_start_addr: usize) -> Self {
+    Self {
+      local_registers: unsafe { LocalRegisters::new(local_mmio_start_addr) },
+      registers: unsafe { Registers::new(periph_mmio_start_addr) },
+      local_handler_table: [None; NUM_LOCAL_IRQS],
+      handler_table: [None; NUM_PERIPHERAL_IRQS],
+      local_enabled: 0,
+      enabled: 0,
+    }
+  }
+
+  /// Pending local IRQs that are enabled; bit n is set if IRQ n is pending
+  fn local_pending(&self) -> u32 {
+    self.local_registers.CORE0_IRQ_SOURCE.get() & self.local_enabled
+  }
+
+  /// Pending peripheral IRQs that are enabled; bit n is set if IRQ n is pending
+  fn pending(&self) -> u64 {
+    let pending = ((self.registers.PENDING_2.get() as u64) << 32) | self.registers.PENDING_1.get() as u64;
+
+    pending & self.enabled
+  }
+}
+
+impl InterruptController {
+  pub const COMPATIBLE: &'static str = "BCM Interrupt Controller";
+
+  pub const MAX_LOCAL_IRQ_NUMBER:      usize = 11;
+  pub const MAX_PERIPHERAL_IRQ_NUMBER: usize = 63;
+
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide valid MMIO start addresses of the local and the peripheral interrupt controller
+  pub const unsafe fn new(local_mmio_start_addr: usize, periph_mmio_start_addr: usize) -> Self {
+    Self {
+      inner: IRQSafeNullLock::new(unsafe {
+        InterruptControllerInner::new(local_mmio_start_addr, periph_mmio_start_addr)
+      }),
+    }
+  }
+}
+
+impl driver::interface::DeviceDriver for InterruptController {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+
+  unsafe fn init(&self) -> Result<(), &'static str> {
+    // Start from a clean slate; the firmware might have left IRQs enabled
+    self.inner.lock(|i| {
+      i.local_registers.CORE0_TIMER_IRQCNTL.set(0);
+      i.registers.DISABLE_1.set(u32::MAX);
+      i.registers.DISABLE_2.set(u32::MAX);
+    });
+
+    Ok(())
+  }
+}
+
+impl interface::IRQManager for InterruptController {
+  type IRQNumberType = IRQNumber;
+
+  fn register_handler(&self, descriptor: IRQHandlerDescriptor<Self::IRQNumberType>) -> Result<(), &'static str> {
+    self.inner.lock(|i| {
+      let slot = match descriptor.number() {
+        IRQNumber::Local(lirq) if lirq.get() <= MAX_CORE_TIMER_IRQ_NUMBER => &mut i.local_handler_table[lirq.get()],
+        IRQNumber::Local(_)                                               => return Err("Only local core timer IRQs are supported"),
+        IRQNumber::Peripheral(pirq)                                       => &mut i.handler_table[pirq.get()],
+      };
+
+      if slot.is_some() { return Err("IRQ handler already registered"); }
+
+      *slot = Some(descriptor);
+
+      Ok(())
+    })
+  }
+
+  fn enable(&self, irq: &Self::IRQNumberType) {
+    match irq {
+      IRQNumber::Local(lirq) => {
+        let number = lirq.get();
+
+        assert!(number <= MAX_CORE_TIMER_IRQ_NUMBER, "Only local core timer IRQs are supported");
+
+        self.inner.lock(|i| {
+          i.local_enabled |= 1 << number;
+          i.local_registers.CORE0_TIMER_IRQCNTL.set(i.local_enabled);
+        });
+      },
+      IRQNumber::Peripheral(pirq) => {
+        let number = pirq.get();
+
+        self.inner.lock(|i| {
+          if number < 32 {
+            i.registers.ENABLE_1.set(1 << number);
+          } else {
+            i.registers.ENABLE_2.set(1 << (number - 32));
+          }
+
+          i.enabled |= 1 << number;
+        });
+      },
+    }
+  }
+
+  fn handle_pending_irqs(&self) {
+    let mut local_pending = self.inner.lock(|i| i.local_pending());
+
+    while local_pending != 0 {
+      let number = local_pending.trailing_zeros() as usize;
+      local_pending &= local_pending - 1;
+
+      match self.inner.lock(|i| i.local_handler_table[number]) {
+        None             => panic!("No handler registered for local IRQ {}", number),
+        Some(descriptor) => descriptor.handler().handle().expect("Error handling IRQ"),
+      }
+    }
+
+    let mut pending = self.inner.lock(|i| i.pending());
+
+    while pending != 0 {
+      let number = pending.trailing_zeros() as usize;
+      pending &= pending - 1;
+
+      match self.inner.lock(|i| i.handler_table[number]) {
+        None             => panic!("No handler registered for IRQ {}", number),
+        Some(descriptor) => descriptor.handler().handle().expect("Error handling IRQ"),
+      }
+    }
+  }
+
+  fn print_handler(&self) {
+    self.inner.lock(|i| {
+      i.
+        local_handler_table.
+        iter().
+        chain(i.handler_table.iter()).
+        flatten().
+        for_each(|d| info!("\t{}", d))
+    });
+  }
+}
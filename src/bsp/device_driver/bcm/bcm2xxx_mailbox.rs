@@ -0,0 +1,419 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! VideoCore mailbox driver
+//!
+//! The ARM talks to the VideoCore firmware through the mailboxes; property tag messages go over channel 8
+//! A message is a 16-byte aligned buffer of 32 bit words:
+//! - The buffer size in bytes and a request/response code
+//! - A sequence of tags; each one is a tag id, the value buffer size in bytes, a request/response code and the value buffer
+//! - An end tag (0)
+//!
+//! The firmware overwrites each tag's request values with its response
+
+use core::time::Duration;
+
+use tock_registers::{
+  interfaces::{
+    Readable,
+    Writeable,
+  },
+  register_bitfields,
+  register_structs,
+  registers::{
+    ReadOnly,
+    WriteOnly,
+  },
+};
+
+use crate::{
+  bsp::device_driver::common::MMIODerefWrapper,
+  cpu,
+  driver,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  time,
+};
+
+register_bitfields! {
+  u32,
+
+  /// Mailbox Status Register
+  STATUS [
+    /// No space for another message
+    FULL OFFSET(31) NUMBITS(1) [],
+
+    /// No message to read
+    EMPTY OFFSET(30) NUMBITS(1) []
+  ]
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  RegisterBlock {
+    /// Mailbox 0 (VideoCore to ARM) read
+    (0x00 => READ: ReadOnly<u32>),
+    (0x04 => _reserved1),
+    /// Mailbox 0 status
+    (0x18 => READ_STATUS: ReadOnly<u32, STATUS::Register>),
+    (0x1c => _reserved2),
+    /// Mailbox 1 (ARM to VideoCore) write
+    (0x20 => WRITE: WriteOnly<u32>),
+    (0x24 => _reserved3),
+    /// Mailbox 1 status
+    (0x38 => WRITE_STATUS: ReadOnly<u32, STATUS::Register>),
+    (0x3c => @END),
+  }
+}
+
+/// Abstraction for the associated MMIO registers
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// The property tags channel; ARM to VideoCore
+const CHANNEL_PROPERTY: u32 = 8;
+
+/// How long the firmware gets to answer
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The VideoCore sees ARM physical memory through this uncached alias
+const VC_BUS_ALIAS: u32 = 0xC000_0000;
+
+const CODE_REQUEST:          u32 = 0x0000_0000;
+const CODE_RESPONSE_SUCCESS: u32 = 0x8000_0000;
+
+/// Set in a tag's request/response code once the firmware has answered; the remaining bits are the response length in bytes
+const TAG_RESPONSE: u32 = 0x8000_0000;
+
+/// The size of a property message buffer in words; a whole number of cache lines
+const BUFFER_WORDS: usize = 64;
+
+/// Property tags
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+#[repr(u32)]
+pub enum Tag {
+  GetFirmwareRevision = 0x0000_0001,
+  GetBoardModel       = 0x0001_0001,
+  GetBoardRevision    = 0x0001_0002,
+  GetBoardMacAddress  = 0x0001_0003,
+  GetBoardSerial      = 0x0001_0004,
+  GetArmMemory        = 0x0001_0005,
+  GetVcMemory         = 0x0001_0006,
+  GetPowerState       = 0x0002_0001,
+  SetPowerState       = 0x0002_8001,
+  GetClockRate        = 0x0003_0002,
+  GetMaxClockRate     = 0x0003_0004,
+  GetTemperature      = 0x0003_0006,
+  GetMaxTemperature   = 0x0003_000a,
+  AllocateBuffer      = 0x0004_0001,
+  GetPitch            = 0x0004_0008,
+  SetPhysicalSize     = 0x0004_8003,
+  SetVirtualSize      = 0x0004_8004,
+  SetDepth            = 0x0004_8005,
+  SetVirtualOffset    = 0x0004_8009,
+}
+
+/// Clock ids
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+#[repr(u32)]
+pub enum Clock {
+  Emmc  = 1,
+  Uart  = 2,
+  Arm   = 3,
+  Core  = 4,
+  V3d   = 5,
+  H264  = 6,
+  Isp   = 7,
+  Sdram = 8,
+  Pixel = 9,
+  Pwm   = 10,
+  Emmc2 = 12,
+}
+
+/// A framebuffer allocated by the firmware
+#[derive(Copy, Clone)]
+pub struct FramebufferInfo {
+  /// ARM physical address
+  pub base: usize,
+
+  /// Size in bytes
+  pub size: usize,
+
+  pub width: u32,
+  pub height: u32,
+
+  /// Bytes per line
+  pub pitch: u32,
+
+  /// Bits per pixel
+  pub depth: u32,
+}
+
+/// Cache line aligned and sized; so nothing else shares the lines invalidated once the firmware has answered
+#[repr(C, align(64))]
+struct Buffer([u32; BUFFER_WORDS]);
+
+const _: () = assert!(size_of::<Buffer>().is_multiple_of(64), "Property buffer must be whole cache lines");
+
+/// Identifies a tag within a property message; to read its response
+#[derive(Copy, Clone)]
+pub struct TagHandle {
+  offset: usize,
+  value_words: usize,
+}
+
+/// A property tag message under construction
+///
+/// Tags are appended with `add_tag`; once the message went through `Mailbox::call` each tag's response is read with `response`
+pub struct PropertyMessage {
+  buffer: Buffer,
+
+  /// Words in use; the size and code words included
+  len: usize,
+}
+
+struct MailboxInner {
+  registers: Registers,
+}
+
+/// Representation of the mailbox
+pub struct Mailbox {
+  inner: NullLock<MailboxInner>,
+}
+
+unsafe impl Sync for Mailbox {}
+
+impl PropertyMessage {
+  /// Create an empty message
+  pub const fn new() -> Self {
+    Self {
+      buffer: Buffer([0; BUFFER_WORDS]),
+      len: 2,
+    }
+  }
+
+  /// Append `tag` with `request` as its values; the value buffer fits the larger of the request and `response_words`
+  pub fn add_tag(&mut self, tag: Tag, request: &[u32], response_words: usize) -> Result<TagHandle, &'static str> {
+    let value_words = request.len().max(response_words);
+
+    // The tag header, its values and the end tag
+    if self.len + 3 + value_words + 1 > BUFFER_WORDS { return Err("Property message full"); }
+
+    let words = &mut self.buffer.0[self.len..self.len + 3 + value_words];
+
+    words[0] = tag as u32;
+    words[1] = (value_words * 4) as u32;
+    words[2] = CODE_REQUEST;
+    words[3..3 + request.len()].copy_from_slice(request);
+    words[3 + request.len()..].fill(0);
+
+    let handle = TagHandle {
+      offset: self.len,
+      value_words,
+    };
+
+    self.len += 3 + value_words;
+
+    Ok(handle)
+  }
+
+  /// The response values of `tag`
+  pub fn response(&self, tag: TagHandle) -> Result<&[u32], &'static str> {
+    let code = self.buffer.0[tag.offset + 2];
+
+    if code & TAG_RESPONSE == 0 { return Err("Firmware did not answer the tag"); }
+
+    let words = ((code & !TAG_RESPONSE) as usize).div_ceil(4).min(tag.value_words);
+
+    Ok(&self.buffer.0[tag.offset + 3..tag.offset + 3 + words])
+  }
+
+  /// Add the end tag and fill in the header
+  fn finish(&mut self) {
+    self.buffer.0[self.len] = 0;
+    self.buffer.0[0] = ((self.len + 1) * 4) as u32;
+    self.buffer.0[1] = CODE_REQUEST;
+  }
+}
+
+impl MailboxInner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      registers: unsafe { Registers::new(mmio_start_addr) },
+    }
+  }
+
+  /// Spin until `done` holds; or fail once `deadline` has passed
+  fn wait_until(&self, deadline: Duration, done: impl Fn(&Self) -> bool) -> Result<(), &'static str> {
+    while !done(self) {
+      if time::time_manager().uptime() > deadline { return Err("Mailbox timeout"); }
+
+      cpu::nop();
+    }
+
+    Ok(())
+  }
+
+  /// Send `message` to the firmware and wait for its response
+  fn call(&mut self, message: &mut PropertyMessage) -> Result<(), &'static str> {
+    message.finish();
+
+    let buffer = &message.buffer as *const Buffer as usize;
+    let size = message.len * 4 + 4;
+
+    // The lowest 4 bits carry the channel; the buffer's cache line alignment keeps them clear
+    let request = (buffer as u32 | VC_BUS_ALIAS) | CHANNEL_PROPERTY;
+    let deadline = time::time_manager().uptime() + TIMEOUT;
+
+    // The VideoCore doesn't see the ARM's caches
+    cpu::clean_invalidate_dcache_range(buffer, size);
+
+    self.wait_until(deadline, |i| !i.registers.WRITE_STATUS.is_set(STATUS::FULL))?;
+    self.registers.WRITE.set(request);
+
+    // Skip responses to other channels
+    loop {
+      self.wait_until(deadline, |i| !i.registers.READ_STATUS.is_set(STATUS::EMPTY))?;
+
+      if self.registers.READ.get() == request { break; }
+    }
+
+    // Invalidate only; cleaning could write stale lines back over the response
+    cpu::invalidate_dcache_range(buffer, size);
+
+    if message.buffer.0[1] != CODE_RESPONSE_SUCCESS { return Err("Firmware could not process the message"); }
+
+    Ok(())
+  }
+}
+
+impl Mailbox {
+  pub const COMPATIBLE: &'static str = "BCM VideoCore Mailbox";
+
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      inner: NullLock::new(unsafe { MailboxInner::new(mmio_start_addr) }),
+    }
+  }
+
+  /// Send `message` to the firmware and wait for its response
+  pub fn call(&self, message: &mut PropertyMessage) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.call(message))
+  }
+
+  /// Send a message with just `tag` and return the first `N` words of its response
+  fn query<const N: usize>(&self, tag: Tag, request: &[u32]) -> Result<[u32; N], &'static str> {
+    let mut message = PropertyMessage::new();
+    let handle = message.add_tag(tag, request, N)?;
+
+    self.call(&mut message)?;
+
+    message.
+      response(handle)?.
+      get(..N).
+      and_then(|r| r.try_into().ok()).
+      ok_or("Firmware response too short")
+  }
+
+  /// The firmware's revision; its build time as a Unix timestamp
+  pub fn firmware_revision(&self) -> Result<u32, &'static str> {
+    self.query::<1>(Tag::GetFirmwareRevision, &[]).map(|[revision]| revision)
+  }
+
+  /// The board revision code
+  pub fn board_revision(&self) -> Result<u32, &'static str> {
+    self.query::<1>(Tag::GetBoardRevision, &[]).map(|[revision]| revision)
+  }
+
+  /// The board's serial number
+  pub fn board_serial(&self) -> Result<u64, &'static str> {
+    self.query::<2>(Tag::GetBoardSerial, &[]).map(|[low, high]| ((high as u64) << 32) | low as u64)
+  }
+
+  /// The base address and size of the memory assigned to the ARM
+  pub fn arm_memory(&self) -> Result<(usize, usize), &'static str> {
+    self.query::<2>(Tag::GetArmMemory, &[]).map(|[base, size]| (base as usize, size as usize))
+  }
+
+  /// The base address and size of the memory assigned to the VideoCore
+  pub fn vc_memory(&self) -> Result<(usize, usize), &'static str> {
+    self.query::<2>(Tag::GetVcMemory, &[]).map(|[base, size]| (base as usize, size as usize))
+  }
+
+  /// The current rate of `clock` in Hz
+  pub fn clock_rate(&self, clock: Clock) -> Result<u32, &'static str> {
+    self.query::<2>(Tag::GetClockRate, &[clock as u32]).map(|[_, rate]| rate)
+  }
+
+  /// The maximum rate of `clock` in Hz
+  pub fn max_clock_rate(&self, clock: Clock) -> Result<u32, &'static str> {
+    self.query::<2>(Tag::GetMaxClockRate, &[clock as u32]).map(|[_, rate]| rate)
+  }
+
+  /// The SoC temperature in thousandths of a degree Celsius
+  pub fn temperature(&self) -> Result<u32, &'static str> {
+    self.query::<2>(Tag::GetTemperature, &[0]).map(|[_, temperature]| temperature)
+  }
+
+  /// The temperature in thousandths of a degree Celsius at which the firmware starts throttling
+  pub fn max_temperature(&self) -> Result<u32, &'static str> {
+    self.query::<2>(Tag::GetMaxTemperature, &[0]).map(|[_, temperature]| temperature)
+  }
+
+  /// Have the firmware allocate a `width` x `height` framebuffer with `depth` bits per pixel
+  ///
+  /// The firmware may adjust the geometry; the returned info holds what it actually set up
+  pub fn allocate_framebuffer(&self, width: u32, height: u32, depth: u32) -> Result<FramebufferInfo, &'static str> {
+    let mut message = PropertyMessage::new();
+
+    let size_tag = message.add_tag(Tag::SetPhysicalSize, &[width, height], 2)?;
+    message.add_tag(Tag::SetVirtualSize, &[width, height], 2)?;
+    message.add_tag(Tag::SetVirtualOffset, &[0, 0], 2)?;
+    let depth_tag = message.add_tag(Tag::SetDepth, &[depth], 1)?;
+    // The request value is the alignment
+    let buffer_tag = message.add_tag(Tag::AllocateBuffer, &[4096], 2)?;
+    let pitch_tag = message.add_tag(Tag::GetPitch, &[], 1)?;
+
+    self.call(&mut message)?;
+
+    let (&[width, height], &[depth], &[base, size], &[pitch]) = (
+      message.response(size_tag)?,
+      message.response(depth_tag)?,
+      message.response(buffer_tag)?,
+      message.response(pitch_tag)?,
+    ) else {
+      return Err("Firmware response too short");
+    };
+
+    if base == 0 || size == 0 { return Err("Firmware did not allocate a framebuffer"); }
+
+    Ok(FramebufferInfo {
+      base: (base & !VC_BUS_ALIAS) as usize,
+      size: size as usize,
+      width,
+      height,
+      pitch,
+      depth,
+    })
+  }
+}
+
+impl driver::interface::DeviceDriver for Mailbox {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+}
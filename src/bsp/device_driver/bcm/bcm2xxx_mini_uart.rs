@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Mini UART driver
+//!
+//! The mini UART is part of the auxiliary peripherals block (AUX); which it shares with two SPI masters
+//! It is a cut-down 16550: 7 or 8 data bits, no parity, one stop bit and 8-deep FIFOs that can't be disabled
+//! Its baud rate is derived from the VPU core clock; so that clock must not change while the UART is in use (`core_freq` in config.txt)
+
+use core::{
+  fmt,
+  task::Waker,
+};
+
+use tock_registers::{
+  interfaces::{
+    ReadWriteable,
+    Readable,
+    Writeable,
+  },
+  register_bitfields,
+  register_structs,
+  registers::ReadWrite,
+};
+
+use crate::{
+  bsp::device_driver::common::{
+    DataBits,
+    MMIODerefWrapper,
+    Parity,
+    StopBits,
+    UartConfig,
+  },
+  console,
+  cpu,
+  driver,
+  exception,
+  synchronization::{
+    IRQSafeNullLock,
+    self,
+  },
+};
+
+register_bitfields! {
+  u32,
+
+  /// Auxiliary Enables
+  AUX_ENABLES [
+    /// Mini UART enable
+    /// If clear the mini UART is disabled; its registers can't be accessed
+    MINI_UART OFFSET(0) NUMBITS(1) [
+      Disabled = 0,
+      Enabled  = 1
+    ]
+  ],
+
+  /// Mini UART I/O Data
+  AUX_MU_IO [
+    /// Received data character; written data character to transmit
+    DATA OFFSET(0) NUMBITS(8) []
+  ],
+
+  /// Mini UART Interrupt Enable
+  AUX_MU_IER [
+    /// Enable the receive interrupt
+    RX OFFSET(0) NUMBITS(1) [],
+
+    /// Enable the transmit interrupt
+    TX OFFSET(1) NUMBITS(1) []
+  ],
+
+  /// Mini UART Interrupt Identify
+  AUX_MU_IIR [
+    /// On write: clear the FIFOs
+    FIFO_CLEAR OFFSET(1) NUMBITS(2) [
+      Rx  = 0b01,
+      Tx  = 0b10,
+      All = 0b11
+    ]
+  ],
+
+  /// Mini UART Line Control
+  AUX_MU_LCR [
+    /// Data size
+    /// Bit 1 is undocumented but has to be set for 8 bit mode to work; see the BCM2835 ARM Peripherals errata
+    DATA_SIZE OFFSET(0) NUMBITS(2) [
+      SevenBit = 0b00,
+      EightBit = 0b11
+    ]
+  ],
+
+  /// Mini UART Line Status
+  AUX_MU_LSR [
+    /// Transmitter idle
+    /// Set if the transmit FIFO is empty and the transmitter is idle; i.e. the last bit has been sent
+    TX_IDLE OFFSET(6) NUMBITS(1) [],
+
+    /// Transmitter empty
+    /// Set if the transmit FIFO can accept at least one byte
+    TX_EMPTY OFFSET(5) NUMBITS(1) [],
+
+    /// Receiver overrun
+    /// Set if a character was received while the receive FIFO was full; cleared each time this register is read
+    RX_OVERRUN OFFSET(1) NUMBITS(1) [],
+
+    /// Data ready
+    /// Set if the receive FIFO holds at least one character
+    DATA_READY OFFSET(0) NUMBITS(1) []
+  ],
+
+  /// Mini UART Extra Control
+  AUX_MU_CNTL [
+    /// Transmitter enable
+    TX_ENABLE OFFSET(1) NUMBITS(1) [
+      Disabled = 0,
+      Enabled  = 1
+    ],
+
+    /// Receiver enable
+    RX_ENABLE OFFSET(0) NUMBITS(1) [
+      Disabled = 0,
+      Enabled  = 1
+    ]
+  ],
+
+  /// Mini UART Baudrate
+  AUX_MU_BAUD [
+    /// The baud rate counter; `baud = core_clock / (8 * (BAUD + 1))`
+    BAUD OFFSET(0) NUMBITS(16) []
+  ]
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  RegisterBlock {
+    (0x00 => _reserved1),
+    (0x04 => AUX_ENABLES: ReadWrite<u32, AUX_ENABLES::Register>),
+    (0x08 => _reserved2),
+    (0x40 => AUX_MU_IO: ReadWrite<u32, AUX_MU_IO::Register>),
+    (0x44 => AUX_MU_IER: ReadWrite<u32, AUX_MU_IER::Register>),
+    (0x48 => AUX_MU_IIR: ReadWrite<u32, AUX_MU_IIR::Register>),
+    (0x4c => AUX_MU_LCR: ReadWrite<u32, AUX_MU_LCR::Register>),
+    (0x50 => AUX_MU_MCR: ReadWrite<u32>),
+    (0x54 => AUX_MU_LSR: ReadWrite<u32, AUX_MU_LSR::Register>),
+    (0x58 => _reserved3),
+    (0x60 => AUX_MU_CNTL: ReadWrite<u32, AUX_MU_CNTL::Register>),
+    (0x64 => _reserved4),
+    (0x68 => AUX_MU_BAUD: ReadWrite<u32, AUX_MU_BAUD::Register>),
+    (0x6c => @END),
+  }
+}
+
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+/// The VPU core clock rate with `enable_uart=1` in config.txt; used until the actual rate is known
+#[cfg(feature = "bsp_rpi3")]
+const DEFAULT_CLOCK_HZ: u32 = 250_000_000;
+
+/// The VPU core clock rate with `enable_uart=1` in config.txt; used until the actual rate is known
+#[cfg(feature = "bsp_rpi4")]
+const DEFAULT_CLOCK_HZ: u32 = 500_000_000;
+
+struct MiniUartInner {
+  registers: Registers,
+  config: UartConfig,
+  clock_hz: u32,
+  chars_written: usize,
+  chars_read: usize,
+  rx_overruns: usize,
+
+  /// Woken by the next RX interrupt; which is only enabled while there is one
+  rx_waker: Option<Waker>,
+}
+
+/// Representation of the mini UART
+pub struct MiniUart {
+  inner: IRQSafeNullLock<MiniUartInner>,
+}
+
+unsafe impl Sync for MiniUart {}
+
+impl MiniUartInner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      registers: unsafe { Registers::new(mmio_start_addr) },
+      config: UartConfig::DEFAULT,
+      clock_hz: DEFAULT_CLOCK_HZ,
+      chars_written: 0,
+      chars_read: 0,
+      rx_overruns: 0,
+      rx_waker: None,
+    }
+  }
+
+  /// Enable the mini UART and setup baud rate and characteristics from the current config and core clock
+  pub fn init(&mut self) -> Result<(), &'static str> {
+    self.registers.AUX_ENABLES.modify(AUX_ENABLES::MINI_UART::Enabled);
+
+    self.apply_config(self.config)
+  }
+
+  /// Compute the baud rate counter for `baud`; rounded to the nearest achievable rate
+  ///
+  /// E.g. 250 MHz and 921_600 baud: `250_000_000 / (8 * 921_600) - 1 = 32.9` - which gives `BAUD = 33` and an actual rate of 919_118 baud
+  fn baud_counter(&self, baud: u32) -> Result<u32, &'static str> {
+    if baud == 0 { return Err("Baud rate must not be 0"); }
+
+    let divisor = (self.clock_hz as u64 + 4 * baud as u64) / (8 * baud as u64);
+
+    if divisor == 0 || divisor > 0x1_0000 { return Err("Baud rate not reachable with the core clock"); }
+
+    Ok(divisor as u32 - 1)
+  }
+
+  /// Program the line settings and baud rate
+  fn apply_config(&mut self, config: UartConfig) -> Result<(), &'static str> {
+    // Validate before touching the hardware so that a bad config leaves the UART working
+    let data_size = match config.data_bits {
+      DataBits::Seven => AUX_MU_LCR::DATA_SIZE::SevenBit,
+      DataBits::Eight => AUX_MU_LCR::DATA_SIZE::EightBit,
+      _               => return Err("The mini UART only supports 7 or 8 data bits"),
+    };
+
+    if config.parity != Parity::None { return Err("The mini UART does not support parity"); }
+    if config.stop_bits != StopBits::One { return Err("The mini UART only supports one stop bit"); }
+    if !config.fifo { return Err("The mini UART's FIFOs can't be disabled"); }
+
+    let counter = self.baud_counter(config.baud)?;
+
+    // Don't cut off characters that are still being sent
+    self.flush();
+
+    // Turn the UART off temporarily
+    self.registers.AUX_MU_CNTL.set(0);
+
+    // No modem control; and the RX interrupt only while a task waits for it
+    self.registers.AUX_MU_IER.write(AUX_MU_IER::RX.val(u32::from(self.rx_waker.is_some())));
+    self.registers.AUX_MU_MCR.set(0);
+    self.registers.AUX_MU_IIR.write(AUX_MU_IIR::FIFO_CLEAR::All);
+
+    self.registers.AUX_MU_LCR.write(data_size);
+    self.registers.AUX_MU_BAUD.write(AUX_MU_BAUD::BAUD.val(counter));
+
+    // Turn the UART on
+    self.registers.AUX_MU_CNTL.write(
+      AUX_MU_CNTL::RX_ENABLE::Enabled
+      +
+      AUX_MU_CNTL::TX_ENABLE::Enabled
+    );
+
+    self.config = config;
+
+    Ok(())
+  }
+
+  /// Change the core clock rate the baud rate counter is computed from; re-applies the current config
+  fn set_clock_rate(&mut self, clock_hz: u32) -> Result<(), &'static str> {
+    let previous = self.clock_hz;
+    self.clock_hz = clock_hz;
+
+    let result = self.apply_config(self.config);
+    if result.is_err() { self.clock_hz = previous; }
+
+    result
+  }
+
+  /// Send a character
+  fn write_char(&mut self, c: char) {
+    // Spin until the TX FIFO can take another character
+    while !self.registers.AUX_MU_LSR.is_set(AUX_MU_LSR::TX_EMPTY) {
+      cpu::nop();
+    }
+
+    self.registers.AUX_MU_IO.write(AUX_MU_IO::DATA.val(c as u32));
+
+    self.chars_written += 1;
+  }
+
+  /// Block execution until the last buffered character has been physically put in the TX wire
+  fn flush(&self) {
+    // The UART is off until `init`; and TX_IDLE never gets set then
+    if !self.registers.AUX_ENABLES.is_set(AUX_ENABLES::MINI_UART) { return; }
+
+    while !self.registers.AUX_MU_LSR.is_set(AUX_MU_LSR::TX_IDLE) {
+      cpu::nop();
+    }
+  }
+
+  /// Retrieve a character
+  fn read_char(&mut self) -> Option<char> {
+    // Reading LSR clears the overrun flag; so it must be checked on every read
+    let status = self.registers.AUX_MU_LSR.extract();
+
+    if status.is_set(AUX_MU_LSR::RX_OVERRUN) { self.rx_overruns += 1; }
+
+    // Nothing to read if the RX FIFO is empty
+    if !status.is_set(AUX_MU_LSR::DATA_READY) { return None; }
+
+    let ret = self.registers.AUX_MU_IO.read(AUX_MU_IO::DATA) as u8 as char;
+
+    self.chars_read += 1;
+
+    Some(ret)
+  }
+
+  /// Keep `waker` for the next RX interrupt and enable it
+  fn wake_on_rx(&mut self, waker: &Waker) {
+    self.rx_waker = Some(waker.clone());
+    self.registers.AUX_MU_IER.write(AUX_MU_IER::RX::SET);
+  }
+
+  /// Disable the RX interrupt again; the waker kept for it
+  fn take_rx_waker(&mut self) -> Option<Waker> {
+    self.registers.AUX_MU_IER.set(0);
+
+    self.rx_waker.take()
+  }
+}
+
+impl fmt::Write for MiniUartInner {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for c in s.chars() { self.write_char(c); }
+
+    Ok(())
+  }
+}
+
+impl MiniUart {
+  pub const COMPATIBLE: &'static str = "BCM mini UART";
+
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      inner: IRQSafeNullLock::new(unsafe { MiniUartInner::new(mmio_start_addr) }),
+    }
+  }
+
+  /// Change line settings and baud rate
+  pub fn set_config(&self, config: UartConfig) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.apply_config(config))
+  }
+
+  /// The current line settings and baud rate
+  pub fn config(&self) -> UartConfig {
+    self.inner.lock(|i| i.config)
+  }
+
+  /// Tell the driver the actual core clock rate; e.g. as reported by the firmware
+  pub fn set_clock_rate(&self, clock_hz: u32) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.set_clock_rate(clock_hz))
+  }
+}
+
+use synchronization::interface::Mutex;
+
+impl driver::interface::DeviceDriver for MiniUart {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+
+  unsafe fn init(&self) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.init())
+  }
+}
+
+impl console::interface::Write for MiniUart {
+  fn write_char(&self, c: char) {
+    self.inner.lock(|i| i.write_char(c));
+  }
+
+  fn write_fmt(&self, args: core::fmt::Arguments) -> fmt::Result {
+    self.inner.lock(|i| fmt::Write::write_fmt(i, args))
+  }
+
+  fn flush(&self) {
+    self.inner.lock(|i| i.flush());
+  }
+
+  /// The other end is expected to be a terminal; e.g. `miniterm` or QEMU's stdio
+  fn supports_ansi(&self) -> bool { true }
+}
+
+impl console::interface::Read for MiniUart {
+  fn read_char(&self) -> char {
+    // Poll outside of the lock; spinning inside it would keep IRQs masked until a character arrives
+    loop {
+      if let Some(c) = self.inner.lock(|i| i.read_char()) { return c; }
+
+      cpu::nop();
+    }
+  }
+
+  fn try_read_char(&self) -> Option<char> {
+    self.inner.lock(|i| i.read_char())
+  }
+
+  fn wake_on_rx(&self, waker: &Waker) -> bool {
+    self.inner.lock(|i| i.wake_on_rx(waker));
+
+    true
+  }
+
+  fn clear_rx(&self) {
+    // Read from the RX FIFO until it's empty
+    while self.inner.lock(|i| i.read_char()).is_some() {}
+  }
+}
+
+impl console::interface::Statistics for MiniUart {
+  fn chars_written(&self) -> usize {
+    self.inner.lock(|i| i.chars_written)
+  }
+
+  fn chars_read(&self) -> usize {
+    self.inner.lock(|i| i.chars_read)
+  }
+
+  fn rx_overruns(&self) -> usize {
+    self.inner.lock(|i| i.rx_overruns)
+  }
+}
+
+impl console::interface::All for MiniUart {}
+
+impl exception::asynchronous::interface::IRQHandler for MiniUart {
+  fn handle(&self) -> Result<(), &'static str> {
+    // The interrupt is raised while the RX FIFO holds data; so it stays off until the next `wake_on_rx`
+    let waker = self.inner.lock(|i| i.take_rx_waker());
+
+    if let Some(waker) = waker { waker.wake(); }
+
+    Ok(())
+  }
+}
@@ -4,10 +4,14 @@
 
 //! PL011 UART driver
 
-use core::fmt;
+use core::{
+  fmt,
+  task::Waker,
+};
 
 use tock_registers::{
   interfaces::{
+    ReadWriteable,
     Readable,
     Writeable,
   },
@@ -21,12 +25,19 @@ use tock_registers::{
 };
 
 use crate::{
-  bsp::device_driver::common::MMIODerefWrapper,
+  bsp::device_driver::common::{
+    DataBits,
+    MMIODerefWrapper,
+    Parity,
+    StopBits,
+    UartConfig,
+  },
   console,
   cpu,
   driver,
+  exception,
   synchronization::{
-    NullLock,
+    IRQSafeNullLock,
     self,
   },
 };
@@ -34,6 +45,35 @@ use crate::{
 register_bitfields! {
   u32,
 
+  /// Data Register
+  DR [
+    /// Overrun error
+    /// Set if data is received and the receive FIFO is already full
+    /// The FIFO contents remain valid since no more data is written when the FIFO is full; only the contents of the shift register are overwritten
+    OE OFFSET(11) NUMBITS(1) [],
+
+    /// Break error
+    /// Set if a break condition was detected; the received data input was held LOW for longer than a full-word transmission time
+    BE OFFSET(10) NUMBITS(1) [],
+
+    /// Parity error
+    /// Set if the parity of the received data character does not match the parity selected by LCR_H::EPS and LCR_H::SPS
+    PE OFFSET(9) NUMBITS(1) [],
+
+    /// Framing error
+    /// Set if the received character did not have a valid stop bit
+    FE OFFSET(8) NUMBITS(1) [],
+
+    /// Received data character; written data character to transmit
+    DATA OFFSET(0) NUMBITS(8) []
+  ],
+
+  /// Receive Status Register / Error Clear Register
+  RSR_ECR [
+    /// Meta field for all error flags; a write of any value clears them
+    ALL OFFSET(0) NUMBITS(4) []
+  ],
+
   /// Flag Register
   FR [
     /// Transmit FIFO empty
@@ -76,6 +116,14 @@ register_bitfields! {
 
   /// Line Control Register
   LCR_H [
+    /// Stick parity select
+    /// 0 = stick parity is disabled
+    /// 1 = the parity bit is transmitted and checked as the inverse of the EPS bit
+    SPS OFFSET(7) NUMBITS(1) [
+      Disabled = 0,
+      Enabled  = 1
+    ],
+
     /// Word length
     /// These bits indicate the number of data bits transmitted or received in a frame
     #[allow(clippy::enum_variant_names)]
@@ -92,6 +140,27 @@ register_bitfields! {
     FEN OFFSET(4) NUMBITS(1) [
       FifoDisabled = 0,
       FifoEnabled  = 1
+    ],
+
+    /// Two stop bits select
+    /// If this bit is set to 1, two stop bits are transmitted at the end of the frame
+    /// The receive logic does not check for two stop bits being received
+    STP2 OFFSET(3) NUMBITS(1) [
+      OneStopBit  = 0,
+      TwoStopBits = 1
+    ],
+
+    /// Even parity select
+    /// Only has an effect if parity is enabled through the PEN bit
+    EPS OFFSET(2) NUMBITS(1) [
+      OddParity  = 0,
+      EvenParity = 1
+    ],
+
+    /// Parity enable
+    PEN OFFSET(1) NUMBITS(1) [
+      Disabled = 0,
+      Enabled  = 1
     ]
   ],
 
@@ -124,6 +193,23 @@ register_bitfields! {
     ]
   ],
 
+  /// Interrupt Mask Set/Clear Register
+  IMSC [
+    /// Receive timeout interrupt mask
+    /// Set to let the UARTRTINTR interrupt through; raised when the RX FIFO holds data that hasn't been read for 32 bit periods
+    RTIM OFFSET(6) NUMBITS(1) [
+      Disabled = 0,
+      Enabled  = 1
+    ],
+
+    /// Receive interrupt mask
+    /// Set to let the UARTRXINTR interrupt through; raised when the RX FIFO reaches its trigger level
+    RXIM OFFSET(4) NUMBITS(1) [
+      Disabled = 0,
+      Enabled  = 1
+    ]
+  ],
+
   /// Interrupt Clear Register
   ICR [
     /// Meta field for all pending interrupts
@@ -134,8 +220,9 @@ register_bitfields! {
 register_structs! {
   #[allow(non_snake_case)]
   pub RegisterBlock {
-    (0x00 => DR: ReadWrite<u32>),
-    (0x04 => _reserved1),
+    (0x00 => DR: ReadWrite<u32, DR::Register>),
+    (0x04 => RSR_ECR: ReadWrite<u32, RSR_ECR::Register>),
+    (0x08 => _reserved1),
     (0x18 => FR: ReadOnly<u32, FR::Register>),
     (0x1c => _reserved2),
     (0x24 => IBRD: WriteOnly<u32, IBRD::Register>),
@@ -143,6 +230,8 @@ register_structs! {
     (0x2c => LCR_H: WriteOnly<u32, LCR_H::Register>),
     (0x30 => CR: WriteOnly<u32, CR::Register>),
     (0x34 => _reserved3),
+    (0x38 => IMSC: ReadWrite<u32, IMSC::Register>),
+    (0x3c => _reserved4),
     (0x44 => ICR: WriteOnly<u32, ICR::Register>),
     (0x48 => @END),
   }
@@ -150,20 +239,25 @@ register_structs! {
 
 type Registers = MMIODerefWrapper<RegisterBlock>;
 
-#[derive(PartialEq)]
-enum BlockingMode {
-  Blocking,
-  NonBlocking,
-}
+/// The UART clock rate set by `init_uart_clock` in config.txt; used until the actual rate is known
+const DEFAULT_CLOCK_HZ: u32 = 48_000_000;
 
 struct PL011UartInner {
   registers: Registers,
+  config: UartConfig,
+  clock_hz: u32,
   chars_written: usize,
   chars_read: usize,
+  rx_overruns: usize,
+  framing_errors: usize,
+  parity_errors: usize,
+
+  /// Woken by the next RX interrupt; which is only unmasked while there is one
+  rx_waker: Option<Waker>,
 }
 
 pub struct PL011Uart {
-  inner: NullLock<PL011UartInner>,
+  inner: IRQSafeNullLock<PL011UartInner>,
 }
 
 unsafe impl Sync for PL011Uart {}
@@ -177,19 +271,49 @@ impl PL011UartInner {
   pub const fn new(mmio_start_addr: usize) -> Self {
     Self {
       registers: unsafe { Registers::new(mmio_start_addr) },
+      config: UartConfig::DEFAULT,
+      clock_hz: DEFAULT_CLOCK_HZ,
       chars_written: 0,
       chars_read: 0,
+      rx_overruns: 0,
+      framing_errors: 0,
+      parity_errors: 0,
+      rx_waker: None,
     }
   }
 
-  /// Setup buad rate and characteristics
-  /// 
-  /// This results in 8N1 and 921_600 baud
-  /// The calculation for the BRD is (we set the clocks to 48 MHz in config.txt): `(48_000_000 / 16) / 921_600 = 3.2552083`
-  /// This means `3` (the integer part) goes into `IBRD`
-  /// The `FBRD` calculation (according to the PL011 Technical Reference Manual) is: `INTEGER((0.2552083 * 64) + 0.5) = 16`
-  /// So the generated baud rate divisor is `3 + 16/64 = 3.25` - which results in a generated baud rate of `48_000_000 / (16 * 3.25) = 923_077`
-  pub fn init(&mut self) {
+  /// Setup baud rate and characteristics from the current config and UART clock
+  ///
+  /// The defaults result in 8N1 and 921_600 baud from a 48 MHz clock (set in config.txt)
+  pub fn init(&mut self) -> Result<(), &'static str> {
+    self.apply_config(self.config)
+  }
+
+  /// Compute the baud rate divisor for `baud` as the (IBRD, FBRD) pair
+  ///
+  /// The divisor is `clock / (16 * baud)`; its integer part goes into `IBRD`
+  /// The `FBRD` calculation (according to the PL011 Technical Reference Manual) is: `INTEGER((fraction * 64) + 0.5)`
+  /// So the divisor is computed in 1/64ths and rounded once: `(clock * 64) / (16 * baud) = (clock * 4) / baud`
+  ///
+  /// E.g. 48 MHz and 921_600 baud: `3.2552083` - which gives `IBRD = 3` and `FBRD = 16`
+  fn baud_divisor(&self, baud: u32) -> Result<(u32, u32), &'static str> {
+    if baud == 0 { return Err("Baud rate must not be 0"); }
+
+    let baud = baud as u64;
+    let divisor_x64 = (self.clock_hz as u64 * 4 + baud / 2) / baud;
+    let (ibrd, fbrd) = ((divisor_x64 >> 6) as u32, (divisor_x64 & 0x3f) as u32);
+
+    // From the PL011 Technical Reference Manual: the minimum divisor is 1 and the maximum is 65535 (IBRD = 0xFFFF and FBRD = 0)
+    if ibrd == 0 || divisor_x64 > (0xFFFF << 6) { return Err("Baud rate not reachable with the UART clock"); }
+
+    Ok((ibrd, fbrd))
+  }
+
+  /// Program the line settings and baud rate
+  fn apply_config(&mut self, config: UartConfig) -> Result<(), &'static str> {
+    // Validate before touching the hardware so that a bad config leaves the UART working
+    let (ibrd, fbrd) = self.baud_divisor(config.baud)?;
+
     // Execution can arrive here while there are still characters queued in the TX FIFO and actively being sent out by the UART hardware
     // If the UART is turned of in such a case, the characters will be lost
     // This can happen at runtime on a call to `panic!` because `panic!` initializes its own UART instance
@@ -202,16 +326,32 @@ impl PL011UartInner {
     // Clear all pending interrupts
     self.registers.ICR.write(ICR::ALL::CLEAR);
 
+    let wlen = match config.data_bits {
+      DataBits::Five  => LCR_H::WLEN::FiveBit,
+      DataBits::Six   => LCR_H::WLEN::SixBit,
+      DataBits::Seven => LCR_H::WLEN::SevenBit,
+      DataBits::Eight => LCR_H::WLEN::EightBit,
+    };
+
+    let parity = match config.parity {
+      Parity::None => LCR_H::PEN::Disabled,
+      Parity::Even => LCR_H::PEN::Enabled + LCR_H::EPS::EvenParity,
+      Parity::Odd  => LCR_H::PEN::Enabled + LCR_H::EPS::OddParity,
+    };
+
+    let stop_bits = match config.stop_bits {
+      StopBits::One => LCR_H::STP2::OneStopBit,
+      StopBits::Two => LCR_H::STP2::TwoStopBits,
+    };
+
+    let fifo = if config.fifo { LCR_H::FEN::FifoEnabled } else { LCR_H::FEN::FifoDisabled };
+
     // From the PL011 Technical Reference Manual:
     // The LCR_H, IBRD, and FBRD registers form the single 30-bit wide LCR Register that is updated on a single write strobe generated by a LCR_H write
     // So to internally update the contents fothe IBRD or FBRD an LCR_H write must always be performed at the end
-    self.registers.IBRD.write(IBRD::BAUD_DIVINT.val(3));
-    self.registers.FBRD.write(FBRD::BAUD_DIVFRAC.val(16));
-    self.registers.LCR_H.write(
-      LCR_H::WLEN::EightBit
-      +
-      LCR_H::FEN::FifoEnabled
-    );
+    self.registers.IBRD.write(IBRD::BAUD_DIVINT.val(ibrd));
+    self.registers.FBRD.write(FBRD::BAUD_DIVFRAC.val(fbrd));
+    self.registers.LCR_H.write(wlen + parity + stop_bits + fifo + LCR_H::SPS::Disabled);
 
     // Turn the UART on
     self.registers.CR.write(
@@ -221,6 +361,21 @@ impl PL011UartInner {
       +
       CR::RXE::Enabled
     );
+
+    self.config = config;
+
+    Ok(())
+  }
+
+  /// Change the UART clock rate the baud rate divisor is computed from; re-applies the current config
+  fn set_clock_rate(&mut self, clock_hz: u32) -> Result<(), &'static str> {
+    let previous = self.clock_hz;
+    self.clock_hz = clock_hz;
+
+    let result = self.apply_config(self.config);
+    if result.is_err() { self.clock_hz = previous; }
+
+    result
   }
 
   /// Send a character
@@ -245,30 +400,42 @@ impl PL011UartInner {
   }
 
   /// Retrieve a character
-  fn read_char_converting(&mut self, blocking_mode: BlockingMode) -> Option<char> {
-    // If RX FIFO is empty
-    if self.registers.FR.matches_all(FR::RXFE::SET) {
-      // immediately return in non-blocking mode
-      if blocking_mode == BlockingMode::NonBlocking {
-        return None;
-      }
-
-      // Otherwise, wait until a char is received
-      while self.registers.FR.matches_all(FR::RXFE::SET) {
-        cpu::nop();
-      }
-    }
+  fn read_char(&mut self) -> Option<char> {
+    // Nothing to read if the RX FIFO is empty
+    if self.registers.FR.matches_all(FR::RXFE::SET) { return None; }
+
+    // Read one character along with its error flags
+    let data = self.registers.DR.extract();
 
-    // Read one character
-    let mut ret = self.registers.DR.get() as u8 as char;
+    if data.is_set(DR::OE) { self.rx_overruns += 1; }
+    if data.is_set(DR::FE) { self.framing_errors += 1; }
+    if data.is_set(DR::PE) { self.parity_errors += 1; }
 
-    // Convert carriage return to newline
-    if ret == '\r' { ret = '\n'; }
+    // The error flags are mirrored in RSR; clear them there too
+    if data.any_matching_bits_set(DR::OE::SET + DR::BE::SET + DR::PE::SET + DR::FE::SET) {
+      self.registers.RSR_ECR.write(RSR_ECR::ALL::CLEAR);
+    }
+
+    let ret = data.read(DR::DATA) as u8 as char;
 
     self.chars_read += 1;
 
     Some(ret)
   }
+
+  /// Keep `waker` for the next RX interrupt and unmask it
+  fn wake_on_rx(&mut self, waker: &Waker) {
+    self.rx_waker = Some(waker.clone());
+    self.registers.IMSC.modify(IMSC::RXIM::Enabled + IMSC::RTIM::Enabled);
+  }
+
+  /// Mask the RX interrupts again; the waker kept for them
+  fn take_rx_waker(&mut self) -> Option<Waker> {
+    self.registers.IMSC.modify(IMSC::RXIM::Disabled + IMSC::RTIM::Disabled);
+    self.registers.ICR.write(ICR::ALL::CLEAR);
+
+    self.rx_waker.take()
+  }
 }
 
 /// Implementing `core::fmt::Write` enables usage of the `format_args!` macros - whic hare used to implement the `kernel`'s `print!` and `println!` macros
@@ -292,9 +459,24 @@ impl PL011Uart {
   /// - The caller must provide a valid MMIO start address
   pub const unsafe fn new(mmio_start_addr: usize) -> Self {
     Self {
-      inner: NullLock::new(PL011UartInner::new(mmio_start_addr)),
+      inner: IRQSafeNullLock::new(PL011UartInner::new(mmio_start_addr)),
     }
   }
+
+  /// Change line settings and baud rate
+  pub fn set_config(&self, config: UartConfig) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.apply_config(config))
+  }
+
+  /// The current line settings and baud rate
+  pub fn config(&self) -> UartConfig {
+    self.inner.lock(|i| i.config)
+  }
+
+  /// Tell the driver the actual UART clock rate; e.g. as reported by the firmware
+  pub fn set_clock_rate(&self, clock_hz: u32) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.set_clock_rate(clock_hz))
+  }
 }
 
 use synchronization::interface::Mutex;
@@ -305,9 +487,7 @@ impl driver::interface::DeviceDriver for PL011Uart {
   }
 
   unsafe fn init(&self) -> Result<(), &'static str> {
-    self.inner.lock(|i| i.init());
-
-    Ok(())
+    self.inner.lock(|i| i.init())
   }
 }
 
@@ -320,30 +500,71 @@ impl console::interface::Write for PL011Uart {
     self.inner.lock(|i| fmt::Write::write_fmt(i, args))
   }
 
-  // fn flush(&self) {
-  //   self.inner.lock(|i| i.flush());
-  // }
+  fn flush(&self) {
+    self.inner.lock(|i| i.flush());
+  }
+
+  /// The other end is expected to be a terminal; e.g. `miniterm` or QEMU's stdio
+  fn supports_ansi(&self) -> bool { true }
 }
 
 impl console::interface::Read for PL011Uart {
   fn read_char(&self) -> char {
-    self.inner.lock(|i| i.read_char_converting(BlockingMode::Blocking).unwrap())
+    // Poll outside of the lock; spinning inside it would keep IRQs masked until a character arrives
+    loop {
+      if let Some(c) = self.inner.lock(|i| i.read_char()) { return c; }
+
+      cpu::nop();
+    }
+  }
+
+  fn try_read_char(&self) -> Option<char> {
+    self.inner.lock(|i| i.read_char())
+  }
+
+  fn wake_on_rx(&self, waker: &Waker) -> bool {
+    self.inner.lock(|i| i.wake_on_rx(waker));
+
+    true
   }
 
   fn clear_rx(&self) {
     // Read from ther RX FIFO until it's empty
-    while self.inner.lock(|i| i.read_char_converting(BlockingMode::NonBlocking)).is_some() {}
+    while self.inner.lock(|i| i.read_char()).is_some() {}
   }
 }
 
 impl console::interface::Statistics for PL011Uart {
-  // fn chars_written(&self) -> usize {
-  //   self.inner.lock(|i| i.chars_written)
-  // }
+  fn chars_written(&self) -> usize {
+    self.inner.lock(|i| i.chars_written)
+  }
+
+  fn chars_read(&self) -> usize {
+    self.inner.lock(|i| i.chars_read)
+  }
 
-  // fn chars_read(&self) -> usize {
-  //   self.inner.lock(|i| i.chars_read)
-  // }
+  fn rx_overruns(&self) -> usize {
+    self.inner.lock(|i| i.rx_overruns)
+  }
+
+  fn framing_errors(&self) -> usize {
+    self.inner.lock(|i| i.framing_errors)
+  }
+
+  fn parity_errors(&self) -> usize {
+    self.inner.lock(|i| i.parity_errors)
+  }
 }
 
-impl console::interface::All for PL011Uart {}
\ No newline at end of file
+impl console::interface::All for PL011Uart {}
+
+impl exception::asynchronous::interface::IRQHandler for PL011Uart {
+  fn handle(&self) -> Result<(), &'static str> {
+    // The interrupt stays masked until the next `wake_on_rx`; the characters are left in the FIFO for the woken task
+    let waker = self.inner.lock(|i| i.take_rx_waker());
+
+    if let Some(waker) = waker { waker.wake(); }
+
+    Ok(())
+  }
+}
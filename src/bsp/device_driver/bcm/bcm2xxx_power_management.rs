@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Power management (PM) driver
+//!
+//! The PM block's watchdog resets the board once its countdown runs out; rebooting is a watchdog with a very short countdown
+//! Every register write must carry the PM password in its top byte
+//!
+//! The firmware reads the boot partition from RSTS after a reset; partition 63 tells it to halt instead of booting
+
+use core::time::Duration;
+
+use tock_registers::{
+  interfaces::{
+    ReadWriteable,
+    Readable,
+    Writeable,
+  },
+  register_bitfields,
+  register_structs,
+  registers::ReadWrite,
+};
+
+use crate::{
+  bsp::device_driver::common::MMIODerefWrapper,
+  cpu,
+  driver,
+  power,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+  },
+};
+
+register_bitfields! {
+  u32,
+
+  /// Reset Control Register
+  RSTC [
+    PASSWD OFFSET(24) NUMBITS(8) [
+      Magic = 0x5a
+    ],
+
+    /// What happens when the watchdog runs out
+    WRCFG OFFSET(4) NUMBITS(2) [
+      Clear     = 0b00,
+      FullReset = 0b10
+    ]
+  ],
+
+  /// Watchdog Register
+  WDOG [
+    PASSWD OFFSET(24) NUMBITS(8) [
+      Magic = 0x5a
+    ],
+
+    /// The countdown in ticks of 1/65536 s
+    TIME OFFSET(0) NUMBITS(20) []
+  ]
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  RegisterBlock {
+    (0x00 => _reserved1),
+    (0x1c => RSTC: ReadWrite<u32, RSTC::Register>),
+    /// Reset Status Register; the odd bits report the reset cause, so it is written raw
+    (0x20 => RSTS: ReadWrite<u32>),
+    (0x24 => WDOG: ReadWrite<u32, WDOG::Register>),
+    (0x28 => @END),
+  }
+}
+
+/// Abstraction for the associated MMIO registers
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+const TICKS_PER_SEC: u64 = 65536;
+
+const PASSWORD: u32 = 0x5a00_0000;
+
+/// The boot partition's 6 bits are spread over the even bits 0 to 10 of RSTS
+const PARTITION_MASK: u32 = 0x555;
+const PARTITION_BOOT: u32 = 0x000;
+
+/// Partition 63
+const PARTITION_HALT: u32 = 0x555;
+
+/// The countdown used to reboot
+const REBOOT_TICKS: u32 = 10;
+
+struct PowerManagementInner {
+  registers: Registers,
+
+  /// The countdown the watchdog was started with; 0 if it is stopped
+  watchdog_ticks: u32,
+}
+
+/// Representation of the PM block
+pub struct PowerManagement {
+  inner: IRQSafeNullLock<PowerManagementInner>,
+}
+
+unsafe impl Sync for PowerManagement {}
+
+impl PowerManagementInner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      registers: unsafe { Registers::new(mmio_start_addr) },
+      watchdog_ticks: 0,
+    }
+  }
+
+  /// Start the countdown; the board resets once it runs out
+  fn start_countdown(&mut self, ticks: u32) {
+    self.registers.WDOG.write(WDOG::PASSWD::Magic + WDOG::TIME.val(ticks));
+    self.registers.RSTC.modify(RSTC::PASSWD::Magic + RSTC::WRCFG::FullReset);
+  }
+
+  fn stop_countdown(&mut self) {
+    self.registers.RSTC.modify(RSTC::PASSWD::Magic + RSTC::WRCFG::Clear);
+  }
+
+  /// Reset into the boot partition `partition`
+  fn reset(&mut self, partition: u32) {
+    let rsts = self.registers.RSTS.get() & !(PARTITION_MASK | PASSWORD);
+
+    self.registers.RSTS.set(PASSWORD | rsts | partition);
+    self.start_countdown(REBOOT_TICKS);
+  }
+}
+
+impl PowerManagement {
+  pub const COMPATIBLE: &'static str = "BCM Power Management";
+
+  /// The longest watchdog timeout
+  pub const MAX_TIMEOUT: Duration = Duration::from_micros(((1 << 20) - 1) * 1_000_000 / TICKS_PER_SEC);
+
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      inner: IRQSafeNullLock::new(unsafe { PowerManagementInner::new(mmio_start_addr) }),
+    }
+  }
+}
+
+impl driver::interface::DeviceDriver for PowerManagement {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+}
+
+impl power::interface::PowerManager for PowerManagement {
+  fn reboot(&self) -> ! {
+    self.inner.lock(|i| i.reset(PARTITION_BOOT));
+
+    cpu::wait_forever()
+  }
+
+  fn halt(&self) -> ! {
+    self.inner.lock(|i| i.reset(PARTITION_HALT));
+
+    cpu::wait_forever()
+  }
+}
+
+impl power::interface::Watchdog for PowerManagement {
+  fn start(&self, timeout: Duration) -> Result<(), &'static str> {
+    if timeout > Self::MAX_TIMEOUT { return Err("Watchdog timeout too long"); }
+
+    let ticks = (timeout.as_micros() as u64 * TICKS_PER_SEC / 1_000_000) as u32;
+
+    if ticks == 0 { return Err("Watchdog timeout too short"); }
+
+    self.inner.lock(|i| {
+      i.watchdog_ticks = ticks;
+      i.start_countdown(ticks);
+    });
+
+    Ok(())
+  }
+
+  fn kick(&self) {
+    self.inner.lock(|i| if i.watchdog_ticks != 0 { i.start_countdown(i.watchdog_ticks) });
+  }
+
+  fn stop(&self) {
+    self.inner.lock(|i| {
+      i.watchdog_ticks = 0;
+      i.stop_countdown();
+    });
+  }
+}
+
+impl power::interface::All for PowerManagement {}
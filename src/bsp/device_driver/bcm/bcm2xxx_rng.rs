@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! BCM2835 RNG driver
+//!
+//! The generator fills a FIFO with 32 bit words; the first `WARMUP_COUNT` bits after enabling are less random and get discarded
+
+use core::time::Duration;
+
+use tock_registers::{
+  interfaces::{
+    ReadWriteable,
+    Readable,
+    Writeable,
+  },
+  register_bitfields,
+  register_structs,
+  registers::{
+    ReadOnly,
+    ReadWrite,
+  },
+};
+
+use crate::{
+  bsp::device_driver::common::MMIODerefWrapper,
+  cpu,
+  driver,
+  random,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  time,
+};
+
+register_bitfields! {
+  u32,
+
+  /// Control Register
+  CTRL [
+    /// Enable the generator
+    RBGEN OFFSET(0) NUMBITS(1) []
+  ],
+
+  /// Status Register
+  STATUS [
+    /// The number of words in the FIFO
+    AVAILABLE OFFSET(24) NUMBITS(8) [],
+
+    /// The number of bits to discard after enabling
+    WARMUP_COUNT OFFSET(0) NUMBITS(20) []
+  ],
+
+  /// Interrupt Mask Register
+  INT_MASK [
+    /// Don't raise an interrupt when data is available
+    INT_OFF OFFSET(0) NUMBITS(1) []
+  ]
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  RegisterBlock {
+    (0x00 => CTRL: ReadWrite<u32, CTRL::Register>),
+    (0x04 => STATUS: ReadWrite<u32, STATUS::Register>),
+    (0x08 => DATA: ReadOnly<u32>),
+    (0x0c => _reserved1),
+    (0x10 => INT_MASK: ReadWrite<u32, INT_MASK::Register>),
+    (0x14 => @END),
+  }
+}
+
+/// Abstraction for the associated MMIO registers
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+const WARMUP_COUNT: u32 = 0x4_0000;
+
+/// How long to wait for each word; warming up takes the longest
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+struct RNGInner {
+  registers: Registers,
+}
+
+/// Representation of the RNG
+pub struct RNG {
+  inner: NullLock<RNGInner>,
+}
+
+unsafe impl Sync for RNG {}
+
+impl RNGInner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      registers: unsafe { Registers::new(mmio_start_addr) },
+    }
+  }
+
+  fn init(&mut self) {
+    self.registers.STATUS.write(STATUS::WARMUP_COUNT.val(WARMUP_COUNT));
+    self.registers.INT_MASK.modify(INT_MASK::INT_OFF::SET);
+    self.registers.CTRL.modify(CTRL::RBGEN::SET);
+  }
+
+  fn read_word(&mut self) -> Result<u32, &'static str> {
+    let deadline = time::time_manager().uptime() + TIMEOUT;
+
+    while self.registers.STATUS.read(STATUS::AVAILABLE) == 0 {
+      if time::time_manager().uptime() > deadline { return Err("RNG timeout"); }
+
+      cpu::nop();
+    }
+
+    Ok(self.registers.DATA.get())
+  }
+
+  fn fill_bytes(&mut self, buf: &mut [u8]) -> Result<(), &'static str> {
+    for chunk in buf.chunks_mut(4) {
+      let word = self.read_word()?.to_le_bytes();
+
+      chunk.copy_from_slice(&word[..chunk.len()]);
+    }
+
+    Ok(())
+  }
+}
+
+impl RNG {
+  pub const COMPATIBLE: &'static str = "BCM RNG";
+
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      inner: NullLock::new(unsafe { RNGInner::new(mmio_start_addr) }),
+    }
+  }
+}
+
+impl driver::interface::DeviceDriver for RNG {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+
+  unsafe fn init(&self) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.init());
+
+    Ok(())
+  }
+}
+
+impl random::interface::EntropySource for RNG {
+  fn fill_bytes(&self, buf: &mut [u8]) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.fill_bytes(buf))
+  }
+}
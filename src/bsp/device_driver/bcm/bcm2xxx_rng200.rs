@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! BCM2711 RNG200 driver
+//!
+//! The generator fills a FIFO with 32 bit words once it has produced `WARMUP_BITS` bits; those are less random and get discarded
+
+use core::time::Duration;
+
+use tock_registers::{
+  interfaces::{
+    Readable,
+    Writeable,
+  },
+  register_bitfields,
+  register_structs,
+  registers::{
+    ReadOnly,
+    ReadWrite,
+  },
+};
+
+use crate::{
+  bsp::device_driver::common::MMIODerefWrapper,
+  cpu,
+  driver,
+  random,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  time,
+};
+
+register_bitfields! {
+  u32,
+
+  /// Control Register
+  RNG_CTRL [
+    /// Sample rate divider
+    DIV_CTRL OFFSET(13) NUMBITS(2) [],
+
+    /// Enable the generator
+    RBGEN OFFSET(0) NUMBITS(13) [
+      Enable = 0x1fff
+    ]
+  ],
+
+  /// FIFO Count Register
+  FIFO_COUNT [
+    /// The fill level at which the FIFO counts as full
+    THRESHOLD OFFSET(8) NUMBITS(8) [],
+
+    /// The number of words in the FIFO
+    COUNT OFFSET(0) NUMBITS(8) []
+  ]
+}
+
+register_structs! {
+  #[allow(non_snake_case)]
+  RegisterBlock {
+    (0x00 => RNG_CTRL: ReadWrite<u32, RNG_CTRL::Register>),
+    (0x04 => _reserved1),
+    /// The number of bits generated since enabling
+    (0x0c => TOTAL_BIT_COUNT: ReadOnly<u32>),
+    /// The number of bits to discard after enabling
+    (0x10 => TOTAL_BIT_COUNT_THRESHOLD: ReadWrite<u32>),
+    (0x14 => _reserved2),
+    (0x20 => FIFO_DATA: ReadOnly<u32>),
+    (0x24 => FIFO_COUNT: ReadWrite<u32, FIFO_COUNT::Register>),
+    (0x28 => @END),
+  }
+}
+
+/// Abstraction for the associated MMIO registers
+type Registers = MMIODerefWrapper<RegisterBlock>;
+
+const WARMUP_BITS: u32 = 0x4_0000;
+
+/// The generator counts as warmed up once it produced more than this many bits past the threshold
+const WARMUP_DONE_BITS: u32 = 16;
+
+/// A 1 MHz sample rate
+const SAMPLE_RATE_DIVIDER: u32 = 3;
+
+/// How long to wait for each word; warming up takes the longest
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+struct RNG200Inner {
+  registers: Registers,
+}
+
+/// Representation of the RNG200
+pub struct RNG200 {
+  inner: NullLock<RNG200Inner>,
+}
+
+unsafe impl Sync for RNG200 {}
+
+impl RNG200Inner {
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      registers: unsafe { Registers::new(mmio_start_addr) },
+    }
+  }
+
+  fn init(&mut self) {
+    self.registers.TOTAL_BIT_COUNT_THRESHOLD.set(WARMUP_BITS);
+    self.registers.FIFO_COUNT.write(FIFO_COUNT::THRESHOLD.val(2));
+    self.registers.RNG_CTRL.write(RNG_CTRL::DIV_CTRL.val(SAMPLE_RATE_DIVIDER) + RNG_CTRL::RBGEN::Enable);
+  }
+
+  /// Spin until `ready` holds; or fail once `deadline` has passed
+  fn wait_until(&self, deadline: Duration, ready: impl Fn(&Self) -> bool) -> Result<(), &'static str> {
+    while !ready(self) {
+      if time::time_manager().uptime() > deadline { return Err("RNG timeout"); }
+
+      cpu::nop();
+    }
+
+    Ok(())
+  }
+
+  fn fill_bytes(&mut self, buf: &mut [u8]) -> Result<(), &'static str> {
+    let deadline = time::time_manager().uptime() + TIMEOUT;
+
+    self.wait_until(deadline, |i| i.registers.TOTAL_BIT_COUNT.get() > WARMUP_DONE_BITS)?;
+
+    for chunk in buf.chunks_mut(4) {
+      let deadline = time::time_manager().uptime() + TIMEOUT;
+
+      self.wait_until(deadline, |i| i.registers.FIFO_COUNT.read(FIFO_COUNT::COUNT) != 0)?;
+
+      let word = self.registers.FIFO_DATA.get().to_le_bytes();
+
+      chunk.copy_from_slice(&word[..chunk.len()]);
+    }
+
+    Ok(())
+  }
+}
+
+impl RNG200 {
+  pub const COMPATIBLE: &'static str = "BCM RNG200";
+
+  /// Create an instance
+  ///
+  /// # Safety
+  ///
+  /// - The caller must provide a valid MMIO start address
+  pub const unsafe fn new(mmio_start_addr: usize) -> Self {
+    Self {
+      inner: NullLock::new(unsafe { RNG200Inner::new(mmio_start_addr) }),
+    }
+  }
+}
+
+impl driver::interface::DeviceDriver for RNG200 {
+  fn compatible(&self) -> &'static str {
+    Self::COMPATIBLE
+  }
+
+  unsafe fn init(&self) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.init());
+
+    Ok(())
+  }
+}
+
+impl random::interface::EntropySource for RNG200 {
+  fn fill_bytes(&self, buf: &mut [u8]) -> Result<(), &'static str> {
+    self.inner.lock(|i| i.fill_bytes(buf))
+  }
+}
@@ -5,6 +5,7 @@
 //! Common device driver code
 
 use core::{
+  fmt,
   marker::PhantomData,
   ops,
 };
@@ -34,4 +35,102 @@ impl<T> ops::Deref for MMIODerefWrapper<T> {
   fn deref(&self) -> &Self::Target {
     unsafe { &*(self.start_addr as *const _) }
   }
-}
\ No newline at end of file
+}
+
+/// Number of data bits per UART frame
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum DataBits {
+  Five,
+  Six,
+  Seven,
+  Eight,
+}
+
+/// UART parity modes
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Parity {
+  None,
+  Even,
+  Odd,
+}
+
+/// Number of stop bits per UART frame
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum StopBits {
+  One,
+  Two,
+}
+
+/// UART line settings
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct UartConfig {
+  pub baud: u32,
+  pub data_bits: DataBits,
+  pub parity: Parity,
+  pub stop_bits: StopBits,
+  pub fifo: bool,
+}
+
+impl UartConfig {
+  /// 921_600 baud, 8N1 with FIFOs enabled; what `miniterm` and `minipush` expect
+  pub const DEFAULT: Self = Self {
+    baud: 921_600,
+    data_bits: DataBits::Eight,
+    parity: Parity::None,
+    stop_bits: StopBits::One,
+    fifo: true,
+  };
+
+  /// Take the frame format from `frame`; e.g. `8n1` for 8 data bits, no parity and one stop bit
+  pub fn with_frame(self, frame: &str) -> Result<Self, &'static str> {
+    let &[data_bits, parity, stop_bits] = frame.as_bytes() else { return Err("Expected a frame format like 8n1"); };
+
+    let data_bits = match data_bits {
+      b'5' => DataBits::Five,
+      b'6' => DataBits::Six,
+      b'7' => DataBits::Seven,
+      b'8' => DataBits::Eight,
+      _    => return Err("Data bits must be 5 to 8"),
+    };
+
+    let parity = match parity {
+      b'n' => Parity::None,
+      b'e' => Parity::Even,
+      b'o' => Parity::Odd,
+      _    => return Err("Parity must be n, e or o"),
+    };
+
+    let stop_bits = match stop_bits {
+      b'1' => StopBits::One,
+      b'2' => StopBits::Two,
+      _    => return Err("Stop bits must be 1 or 2"),
+    };
+
+    Ok(Self { data_bits, parity, stop_bits, ..self })
+  }
+}
+
+/// The baud rate and frame format; e.g. `115200 8n1`
+impl fmt::Display for UartConfig {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let data_bits = match self.data_bits {
+      DataBits::Five  => 5,
+      DataBits::Six   => 6,
+      DataBits::Seven => 7,
+      DataBits::Eight => 8,
+    };
+
+    let parity = match self.parity {
+      Parity::None => 'n',
+      Parity::Even => 'e',
+      Parity::Odd  => 'o',
+    };
+
+    let stop_bits = match self.stop_bits {
+      StopBits::One => 1,
+      StopBits::Two => 2,
+    };
+
+    write!(f, "{} {}{}{}", self.baud, data_bits, parity, stop_bits)
+  }
+}
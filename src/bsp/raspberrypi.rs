@@ -4,11 +4,17 @@
 
 //! Top-level BSP file for the Raspberry Pi 3 and 4.
 
+pub mod board;
 pub mod cpu;
 pub mod driver;
+pub mod exception;
+pub mod firmware;
 pub mod memory;
 
-/// Board identification
+/// The block device holding the FAT32 partition the firmware boots from
+pub const BOOT_PARTITION: &str = "sd0p1";
+
+/// The board family the BSP was built for; see `board` for the running board
 pub fn board_name() -> &'static str {
   #[cfg(feature = "bsp_rpi3")]
   { "Raspberry Pi 3" }
@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Board detection
+//!
+//! The firmware reports the board revision code; new-style codes (bit 23 set) are laid out as:
+//! - Bits  0 to  3: board revision
+//! - Bits  4 to 11: model
+//! - Bits 12 to 15: SoC
+//! - Bits 16 to 19: manufacturer
+//! - Bits 20 to 22: memory size
+//!
+//! Old-style codes are only used by the first Raspberry Pi boards; which all have a BCM2835
+
+use core::fmt;
+
+use crate::{
+  info,
+  warn,
+};
+
+use super::driver::mailbox;
+
+/// The SoCs found on Raspberry Pi boards
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Soc {
+  BCM2835,
+  BCM2836,
+  BCM2837,
+  BCM2711,
+  BCM2712,
+}
+
+/// A decoded board revision code
+#[derive(Copy, Clone)]
+pub struct Revision(u32);
+
+/// The SoC the BSP was compiled for
+#[cfg(feature = "bsp_rpi3")]
+const BSP_SOC: Soc = Soc::BCM2837;
+
+/// The SoC the BSP was compiled for
+#[cfg(feature = "bsp_rpi4")]
+const BSP_SOC: Soc = Soc::BCM2711;
+
+impl fmt::Display for Soc {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Soc::BCM2835 => "BCM2835",
+      Soc::BCM2836 => "BCM2836",
+      Soc::BCM2837 => "BCM2837",
+      Soc::BCM2711 => "BCM2711",
+      Soc::BCM2712 => "BCM2712",
+    };
+
+    write!(f, "{}", name)
+  }
+}
+
+impl Revision {
+  /// Wrap a raw revision code
+  pub const fn new(code: u32) -> Self {
+    Self(code)
+  }
+
+  /// The raw revision code
+  pub const fn code(&self) -> u32 {
+    self.0
+  }
+
+  /// Whether the code uses the new-style layout
+  const fn is_new_style(&self) -> bool {
+    self.0 & (1 << 23) != 0
+  }
+
+  const fn field(&self, shift: u32, bits: u32) -> u32 {
+    (self.0 >> shift) & ((1 << bits) - 1)
+  }
+
+  /// The board model; `None` if it is not known
+  pub fn model(&self) -> Option<&'static str> {
+    if !self.is_new_style() { return Some("Raspberry Pi 1"); }
+
+    let model = match self.field(4, 8) {
+      0x00 => "Raspberry Pi 1 Model A",
+      0x01 => "Raspberry Pi 1 Model B",
+      0x02 => "Raspberry Pi 1 Model A+",
+      0x03 => "Raspberry Pi 1 Model B+",
+      0x04 => "Raspberry Pi 2 Model B",
+      0x06 => "Raspberry Pi Compute Module 1",
+      0x08 => "Raspberry Pi 3 Model B",
+      0x09 => "Raspberry Pi Zero",
+      0x0a => "Raspberry Pi Compute Module 3",
+      0x0c => "Raspberry Pi Zero W",
+      0x0d => "Raspberry Pi 3 Model B+",
+      0x0e => "Raspberry Pi 3 Model A+",
+      0x10 => "Raspberry Pi Compute Module 3+",
+      0x11 => "Raspberry Pi 4 Model B",
+      0x12 => "Raspberry Pi Zero 2 W",
+      0x13 => "Raspberry Pi 400",
+      0x14 => "Raspberry Pi Compute Module 4",
+      0x15 => "Raspberry Pi Compute Module 4S",
+      0x17 => "Raspberry Pi 5",
+      0x18 => "Raspberry Pi Compute Module 5",
+      0x19 => "Raspberry Pi 500",
+      0x1a => "Raspberry Pi Compute Module 5 Lite",
+      _    => return None,
+    };
+
+    Some(model)
+  }
+
+  /// The SoC; `None` if it is not known
+  pub fn soc(&self) -> Option<Soc> {
+    if !self.is_new_style() { return Some(Soc::BCM2835); }
+
+    match self.field(12, 4) {
+      0 => Some(Soc::BCM2835),
+      1 => Some(Soc::BCM2836),
+      2 => Some(Soc::BCM2837),
+      3 => Some(Soc::BCM2711),
+      4 => Some(Soc::BCM2712),
+      _ => None,
+    }
+  }
+
+  /// The amount of RAM in MiB; `None` if it is not known
+  pub fn memory_mib(&self) -> Option<usize> {
+    if !self.is_new_style() { return None; }
+
+    match self.field(20, 3) {
+      size @ 0..=6 => Some(256 << size),
+      _            => None,
+    }
+  }
+
+  /// The manufacturer; `None` if it is not known
+  pub fn manufacturer(&self) -> Option<&'static str> {
+    if !self.is_new_style() { return None; }
+
+    match self.field(16, 4) {
+      0     => Some("Sony UK"),
+      1     => Some("Egoman"),
+      2 | 4 => Some("Embest"),
+      3     => Some("Sony Japan"),
+      5     => Some("Stadium"),
+      _     => None,
+    }
+  }
+
+  /// The board revision; `None` for old-style codes
+  pub fn board_revision(&self) -> Option<u32> {
+    if !self.is_new_style() { return None; }
+
+    Some(self.field(0, 4))
+  }
+}
+
+impl fmt::Display for Revision {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.model() {
+      Some(model) => write!(f, "{}", model)?,
+      None        => write!(f, "Unknown model")?,
+    }
+
+    if let Some(revision) = self.board_revision() { write!(f, " rev 1.{}", revision)?; }
+    if let Some(soc) = self.soc() { write!(f, ", {}", soc)?; }
+
+    match self.memory_mib() {
+      Some(mib) if mib >= 1024 => write!(f, ", {} GiB", mib / 1024)?,
+      Some(mib)                => write!(f, ", {} MiB", mib)?,
+      None                     => (),
+    }
+
+    if let Some(manufacturer) = self.manufacturer() { write!(f, ", {}", manufacturer)?; }
+
+    write!(f, " ({:#08x})", self.code())
+  }
+}
+
+/// The revision of the running board; as reported by the firmware
+pub fn revision() -> Result<Revision, &'static str> {
+  mailbox().board_revision().map(Revision::new)
+}
+
+/// The serial number of the running board; as reported by the firmware
+pub fn serial() -> Result<u64, &'static str> {
+  mailbox().board_serial()
+}
+
+/// Print the detected board; and warn if it doesn't match the compiled BSP
+pub fn print_info() {
+  let revision = match revision() {
+    Ok(revision) => revision,
+    Err(e)       => {
+      warn!("Board detection failed: {}", e);
+      return;
+    },
+  };
+
+  info!("Detected board: {}", revision);
+
+  match revision.soc() {
+    Some(soc) if soc == BSP_SOC => (),
+    Some(soc)                   => warn!("Running on a {} but the kernel was built for a {}", soc, BSP_SOC),
+    None                        => warn!("Unknown SoC; the kernel was built for a {}", BSP_SOC),
+  }
+}
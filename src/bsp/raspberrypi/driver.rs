@@ -4,41 +4,225 @@
 
 //! RPi drivers
 
+mod commands;
+
 use core::sync::atomic::{
   AtomicBool,
   Ordering,
 };
 
 use crate::{
+  block,
   bsp::device_driver,
   console,
   driver as generic_driver,
+  exception::{
+    self,
+    asynchronous::IRQHandlerDescriptor,
+  },
+  log::Level,
+  power,
+  random,
+  shell,
+  time,
+  warn,
 };
 
-use super::memory::map::mmio;
+use super::{
+  exception::asynchronous::irq_map,
+  memory::{
+    map::mmio,
+    mmu::vc_memory_range_inclusive,
+  },
+};
 
 static PL011_UART: device_driver::PL011Uart = unsafe { device_driver::PL011Uart::new(mmio::PL011_UART_START) };
 
+static MINI_UART: device_driver::MiniUart = unsafe { device_driver::MiniUart::new(mmio::MINI_UART_START) };
+
 static GPIO: device_driver::GPIO = unsafe { device_driver::GPIO::new(mmio::GPIO_START) };
 
+static MAILBOX: device_driver::Mailbox = unsafe { device_driver::Mailbox::new(mmio::MAILBOX_START) };
+
+static POWER_MANAGEMENT: device_driver::PowerManagement =
+  unsafe { device_driver::PowerManagement::new(mmio::PM_START) };
+
+#[cfg(feature = "bsp_rpi3")]
+static RNG: device_driver::RNG = unsafe { device_driver::RNG::new(mmio::RNG_START) };
+
+#[cfg(feature = "bsp_rpi4")]
+static RNG: device_driver::RNG200 = unsafe { device_driver::RNG200::new(mmio::RNG_START) };
+
+#[cfg(feature = "bsp_rpi3")]
+static EMMC: device_driver::EMMC =
+  unsafe { device_driver::EMMC::new(mmio::EMMC_START, &MAILBOX, device_driver::Clock::Emmc) };
+
+#[cfg(feature = "bsp_rpi4")]
+static EMMC: device_driver::EMMC =
+  unsafe { device_driver::EMMC::new(mmio::EMMC_START, &MAILBOX, device_driver::Clock::Emmc2) };
+
+/// Reads of the FAT and directories hit the same blocks over and over
+static SD_CACHE: block::BlockCache = block::BlockCache::new(&EMMC);
+
+static FRAMEBUFFER: device_driver::Framebuffer = unsafe {
+  device_driver::Framebuffer::new(
+    &MAILBOX,
+    FRAMEBUFFER_WIDTH,
+    FRAMEBUFFER_HEIGHT,
+    vc_memory_range_inclusive,
+  )
+};
+
+/// The framebuffer resolution; 80 x 60 characters
+const FRAMEBUFFER_WIDTH:  u32 = 640;
+const FRAMEBUFFER_HEIGHT: u32 = 480;
+
+#[cfg(feature = "bsp_rpi3")]
+static INTERRUPT_CONTROLLER: device_driver::InterruptController =
+  unsafe { device_driver::InterruptController::new(mmio::LOCAL_IC_START, mmio::PERIPHERAL_IC_START) };
+
+#[cfg(feature = "bsp_rpi4")]
+static INTERRUPT_CONTROLLER: device_driver::GICv2 =
+  unsafe { device_driver::GICv2::new(mmio::GICD_START, mmio::GICC_START) };
+
+/// Whether the mini UART is the primary console instead of the PL011 UART; selected with the `console_mini_uart` feature
+///
+/// Only one of them can be routed to GPIO 14/15 at a time; on boards where the PL011 UART drives Bluetooth, the mini UART is the one on the header
+const MINI_UART_CONSOLE: bool = cfg!(feature = "console_mini_uart");
+
 // This must only be called after a succesful UART driver init
-fn post_uart_init() -> Result<(), &'static str> {
-  console::register_console(&PL011_UART);
+fn post_pl011_uart_init() -> Result<(), &'static str> {
+  console::register_console(device_driver::PL011Uart::COMPATIBLE, &PL011_UART)?;
+
+  // The UART itself only raises RX interrupts while an async read waits for them
+  let irq_manager = exception::asynchronous::irq_manager();
+
+  let uart_descriptor =
+    IRQHandlerDescriptor::new(irq_map::PL011_UART, device_driver::PL011Uart::COMPATIBLE, &PL011_UART);
+
+  irq_manager.register_handler(uart_descriptor)?;
+  irq_manager.enable(&irq_map::PL011_UART);
+
+  Ok(())
+}
+
+// This must only be called after a succesful UART driver init
+fn post_mini_uart_init() -> Result<(), &'static str> {
+  console::register_console(device_driver::MiniUart::COMPATIBLE, &MINI_UART)?;
+
+  // The UART itself only raises RX interrupts while an async read waits for them
+  let irq_manager = exception::asynchronous::irq_manager();
+
+  let uart_descriptor =
+    IRQHandlerDescriptor::new(irq_map::MINI_UART, device_driver::MiniUart::COMPATIBLE, &MINI_UART);
+
+  irq_manager.register_handler(uart_descriptor)?;
+  irq_manager.enable(&irq_map::MINI_UART);
+
+  Ok(())
+}
+
+// This must only be called after a successful interrupt controller driver init
+fn post_interrupt_controller_init() -> Result<(), &'static str> {
+  exception::asynchronous::register_irq_manager(&INTERRUPT_CONTROLLER);
+
+  // The timer itself stays quiet until the scheduler starts the tick
+  let irq_manager = exception::asynchronous::irq_manager();
+
+  let timer_descriptor =
+    IRQHandlerDescriptor::new(irq_map::ARCH_TIMER, time::TimeManager::COMPATIBLE, time::time_manager());
+
+  irq_manager.register_handler(timer_descriptor)?;
+  irq_manager.enable(&irq_map::ARCH_TIMER);
+
   Ok(())
 }
 
 // This must only be called after a successful GPIO driver init
 fn post_gpio_init() -> Result<(), &'static str> {
-  GPIO.map_pl011_uart();
+  if MINI_UART_CONSOLE {
+    GPIO.map_mini_uart(device_driver::MiniUart::COMPATIBLE)?;
+  } else {
+    GPIO.map_pl011_uart(device_driver::PL011Uart::COMPATIBLE)?;
+  }
+
+  #[cfg(feature = "bsp_rpi3")]
+  GPIO.map_emmc(device_driver::EMMC::COMPATIBLE)?;
+
+  let irq_manager = exception::asynchronous::irq_manager();
+
+  irq_manager.register_handler(IRQHandlerDescriptor::new(irq_map::GPIO, device_driver::GPIO::COMPATIBLE, &GPIO))?;
+  irq_manager.enable(&irq_map::GPIO);
+
+  shell::register_commands(&commands::COMMANDS)
+}
+
+// This must only be called after a successful mailbox driver init
+fn post_mailbox_init() -> Result<(), &'static str> {
+  // The UART clocks depend on the firmware configuration; a wrong rate only garbles the console, so don't fail over it
+  let result = if MINI_UART_CONSOLE {
+    MAILBOX.
+      clock_rate(device_driver::Clock::Core).
+      and_then(|rate| MINI_UART.set_clock_rate(rate))
+  } else {
+    MAILBOX.
+      clock_rate(device_driver::Clock::Uart).
+      and_then(|rate| PL011_UART.set_clock_rate(rate))
+  };
+
+  if let Err(e) = result { warn!("Keeping the default UART clock rate: {}", e); }
+
   Ok(())
 }
 
-fn driver_uart() -> Result<(), &'static str> {
-  let uart_descriptor = generic_driver::DeviceDriverDescriptor::new(
-    &PL011_UART,
-    Some(post_uart_init),
+// This must only be called after a successful power management driver init
+fn post_power_management_init() -> Result<(), &'static str> {
+  power::register_power_manager(&POWER_MANAGEMENT);
+
+  Ok(())
+}
+
+// This must only be called after a successful RNG driver init
+fn post_rng_init() -> Result<(), &'static str> {
+  // Random numbers still come from the CSPRNG; so don't fail over it
+  if let Err(e) = random::register_entropy_source(&RNG) { warn!("No hardware entropy: {}", e); }
+
+  Ok(())
+}
+
+// This must only be called after a successful EMMC driver init
+fn post_emmc_init() -> Result<(), &'static str> {
+  if !EMMC.has_card() { return Ok(()); }
+
+  block::add_disk("sd0", &SD_CACHE)
+}
+
+// This must only be called after a successful framebuffer driver init
+fn post_framebuffer_init() -> Result<(), &'static str> {
+  if !FRAMEBUFFER.is_active() { return Ok(()); }
+
+  console::add_sink(device_driver::Framebuffer::COMPATIBLE, &FRAMEBUFFER, Level::Trace)
+}
+
+/// The interrupt controller comes first; so the drivers after it can register IRQ handlers in their post init callbacks
+fn driver_interrupt_controller() -> Result<(), &'static str> {
+  let interrupt_controller_descriptor = generic_driver::DeviceDriverDescriptor::new(
+    &INTERRUPT_CONTROLLER,
+    Some(post_interrupt_controller_init),
   );
 
+  generic_driver::driver_manager().register_driver(interrupt_controller_descriptor);
+
+  Ok(())
+}
+
+fn driver_uart() -> Result<(), &'static str> {
+  let uart_descriptor = if MINI_UART_CONSOLE {
+    generic_driver::DeviceDriverDescriptor::new(&MINI_UART, Some(post_mini_uart_init))
+  } else {
+    generic_driver::DeviceDriverDescriptor::new(&PL011_UART, Some(post_pl011_uart_init))
+  };
+
   generic_driver::driver_manager().register_driver(uart_descriptor);
 
   Ok(())
@@ -55,6 +239,79 @@ fn driver_gpio() -> Result<(), &'static str> {
   Ok(())
 }
 
+/// After the UART; so failing to query the firmware can be reported
+fn driver_mailbox() -> Result<(), &'static str> {
+  let mailbox_descriptor = generic_driver::DeviceDriverDescriptor::new(
+    &MAILBOX,
+    Some(post_mailbox_init),
+  );
+
+  generic_driver::driver_manager().register_driver(mailbox_descriptor);
+
+  Ok(())
+}
+
+fn driver_power_management() -> Result<(), &'static str> {
+  let power_management_descriptor = generic_driver::DeviceDriverDescriptor::new(
+    &POWER_MANAGEMENT,
+    Some(post_power_management_init),
+  );
+
+  generic_driver::driver_manager().register_driver(power_management_descriptor);
+
+  Ok(())
+}
+
+fn driver_rng() -> Result<(), &'static str> {
+  let rng_descriptor = generic_driver::DeviceDriverDescriptor::new(
+    &RNG,
+    Some(post_rng_init),
+  );
+
+  generic_driver::driver_manager().register_driver(rng_descriptor);
+
+  Ok(())
+}
+
+/// After the GPIO, which routes the card pins, and the mailbox, which reports the base clock
+fn driver_emmc() -> Result<(), &'static str> {
+  let emmc_descriptor = generic_driver::DeviceDriverDescriptor::new(
+    &EMMC,
+    Some(post_emmc_init),
+  );
+
+  generic_driver::driver_manager().register_driver(emmc_descriptor);
+
+  Ok(())
+}
+
+/// After the mailbox; which it allocates the framebuffer through
+fn driver_framebuffer() -> Result<(), &'static str> {
+  let framebuffer_descriptor = generic_driver::DeviceDriverDescriptor::new(
+    &FRAMEBUFFER,
+    Some(post_framebuffer_init),
+  );
+
+  generic_driver::driver_manager().register_driver(framebuffer_descriptor);
+
+  Ok(())
+}
+
+/// Change the console UART's line settings and baud rate; e.g. to talk to a 115200 baud terminal
+pub fn set_console_uart_config(config: device_driver::UartConfig) -> Result<(), &'static str> {
+  if MINI_UART_CONSOLE { MINI_UART.set_config(config) } else { PL011_UART.set_config(config) }
+}
+
+/// The console UART's line settings and baud rate
+pub fn console_uart_config() -> device_driver::UartConfig {
+  if MINI_UART_CONSOLE { MINI_UART.config() } else { PL011_UART.config() }
+}
+
+/// The mailbox driver; for querying the firmware
+pub(super) fn mailbox() -> &'static device_driver::Mailbox {
+  &MAILBOX
+}
+
 pub unsafe fn init() -> Result<(), &'static str> {
   static INIT_DONE: AtomicBool = AtomicBool::new(false);
 
@@ -62,8 +319,14 @@ pub unsafe fn init() -> Result<(), &'static str> {
     return Err("Already initialized");
   }
 
+  driver_interrupt_controller()?;
   driver_uart()?;
+  driver_mailbox()?;
   driver_gpio()?;
+  driver_power_management()?;
+  driver_rng()?;
+  driver_emmc()?;
+  driver_framebuffer()?;
 
   INIT_DONE.store(true, Ordering::Relaxed);
 
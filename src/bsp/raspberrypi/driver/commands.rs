@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! GPIO shell commands
+
+use core::{
+  str::SplitWhitespace,
+  sync::atomic::{
+    AtomicUsize,
+    Ordering,
+  },
+};
+
+use super::GPIO;
+use crate::{
+  bsp::device_driver::{
+    Event,
+    Function,
+    Pull,
+    NUM_PINS,
+  },
+  println,
+  shell::{
+    parse_number,
+    Command,
+  },
+};
+
+/// The shell claims pins under this name
+const OWNER: &str = "shell";
+
+pub const COMMANDS: [Command; 1] = [
+  Command::new("gpio",     "gpio <pin> [claim|out|high|low|...|free]...: Pins", gpio),
+];
+
+/// Pin functions by action name
+const FUNCTIONS: [(&str, Function); 8] = [
+  ("in",   Function::Input),
+  ("out",  Function::Output),
+  ("alt0", Function::Alt0),
+  ("alt1", Function::Alt1),
+  ("alt2", Function::Alt2),
+  ("alt3", Function::Alt3),
+  ("alt4", Function::Alt4),
+  ("alt5", Function::Alt5),
+];
+
+/// The edges seen on each watched pin
+static EDGES: [AtomicUsize; NUM_PINS] = [const { AtomicUsize::new(0) }; NUM_PINS];
+
+/// Called in IRQ context; so it only counts
+fn count_edge(pin: usize) {
+  EDGES[pin].fetch_add(1, Ordering::Relaxed);
+}
+
+/// `gpio <pin> <action>...`; then print the pin's level, owner and edge count
+///
+/// The pin must be claimed before any other action; the driver refuses changes to pins owned by someone else
+/// - `claim`, `free`: Claim the pin for the shell; or stop watching it and give it back
+/// - `in`, `out`, `alt0` to `alt5`: Select the pin's function
+/// - `high`, `low`: Drive an output
+/// - `up`, `down`, `nopull`: Select the pull-up/down resistor
+/// - `watch`: Count the pin's edges
+fn gpio(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let pin = parse_number(args.next())?;
+
+  for action in args {
+    match action {
+      "claim"  => GPIO.claim(pin, OWNER)?,
+      "free"   => {
+        GPIO.disable_events(pin, OWNER)?;
+        GPIO.release(pin, OWNER)?;
+      }
+      "high"   => GPIO.set(pin, OWNER)?,
+      "low"    => GPIO.clear(pin, OWNER)?,
+      "up"     => GPIO.set_pull(pin, OWNER, Pull::Up)?,
+      "down"   => GPIO.set_pull(pin, OWNER, Pull::Down)?,
+      "nopull" => GPIO.set_pull(pin, OWNER, Pull::None)?,
+      "watch"  => {
+        EDGES[pin].store(0, Ordering::Relaxed);
+
+        GPIO.enable_event(pin, OWNER, Event::RisingEdge, count_edge)?;
+        GPIO.enable_event(pin, OWNER, Event::FallingEdge, count_edge)?;
+      }
+      _        => {
+        let (_, function) = FUNCTIONS.
+          iter().
+          find(|(name, _)| *name == action).
+          ok_or("Expected claim, free, in, out, alt0 to alt5, high, low, up, down, nopull or watch")?;
+
+        GPIO.set_function(pin, OWNER, *function)?;
+      }
+    }
+  }
+
+  let level = if GPIO.read(pin)? { "high" } else { "low" };
+
+  println!("{} {} ({} edges)", level, GPIO.owner(pin).unwrap_or("unclaimed"), EDGES[pin].load(Ordering::Relaxed));
+
+  Ok(())
+}
@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! BSP asynchronous exception handling
+
+use crate::bsp::device_driver;
+
+/// The board's interrupt number type
+pub type IRQNumber = device_driver::IRQNumber;
+
+/// The IRQ numbers of the board's devices
+#[cfg(feature = "bsp_rpi3")]
+pub(in crate::bsp) mod irq_map {
+  use super::device_driver::{
+    IRQNumber,
+    LocalIRQ,
+    PeripheralIRQ,
+  };
+
+  /// The non-secure EL1 physical timer of core 0 (`CNTPNSIRQ`)
+  pub const ARCH_TIMER: IRQNumber = IRQNumber::Local(LocalIRQ::new(1));
+
+  /// The mini UART and the SPI masters of the auxiliary peripherals (`aux_int`)
+  pub const MINI_UART: IRQNumber = IRQNumber::Peripheral(PeripheralIRQ::new(29));
+
+  /// Events on any GPIO bank (`gpio_int[3]`)
+  pub const GPIO: IRQNumber = IRQNumber::Peripheral(PeripheralIRQ::new(52));
+
+  /// The PL011 UART (`uart_int`)
+  pub const PL011_UART: IRQNumber = IRQNumber::Peripheral(PeripheralIRQ::new(57));
+}
+
+/// The IRQ numbers of the board's devices
+///
+/// The VideoCore peripheral IRQs (numbered as on the BCM2837) start at SPI 96
+#[cfg(feature = "bsp_rpi4")]
+pub(in crate::bsp) mod irq_map {
+  use super::device_driver::IRQNumber;
+
+  /// The non-secure EL1 physical timer; PPI 14 of every core
+  pub const ARCH_TIMER: IRQNumber = IRQNumber::new(30);
+
+  /// The mini UART and the SPI masters of the auxiliary peripherals (`aux_int`)
+  pub const MINI_UART: IRQNumber = IRQNumber::new(96 + 29);
+
+  /// Events on any GPIO bank (`gpio_int[3]`)
+  pub const GPIO: IRQNumber = IRQNumber::new(96 + 52);
+
+  /// The PL011 UART (`uart_int`)
+  pub const PL011_UART: IRQNumber = IRQNumber::new(96 + 57);
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Firmware queries through the VideoCore mailbox
+
+use crate::{
+  bsp::device_driver::Clock,
+  println,
+};
+
+use super::{
+  board,
+  driver::mailbox,
+};
+
+/// Print the board details reported by the firmware
+pub fn print_info() {
+  let mailbox = mailbox();
+
+  match mailbox.firmware_revision() {
+    Ok(revision) => println!("  Firmware revision: {:#010x}", revision),
+    Err(e)       => println!("  Firmware revision: {}", e),
+  }
+
+  match board::revision() {
+    Ok(revision) => println!("  Board revision:    {}", revision),
+    Err(e)       => println!("  Board revision:    {}", e),
+  }
+
+  match mailbox.board_serial() {
+    Ok(serial) => println!("  Serial number:     {:#018x}", serial),
+    Err(e)     => println!("  Serial number:     {}", e),
+  }
+
+  for (name, memory) in [("ARM memory:", mailbox.arm_memory()), ("VC memory: ", mailbox.vc_memory())] {
+    match memory {
+      Ok((_, 0))       => println!("  {}        empty", name),
+      Ok((base, size)) => println!("  {}        {:#010x} - {:#010x} ({} MiB)", name, base, base + (size - 1), size >> 20),
+      Err(e)           => println!("  {}        {}", name, e),
+    }
+  }
+
+  for (name, clock) in [("ARM", Clock::Arm), ("Core", Clock::Core), ("UART", Clock::Uart), ("EMMC", Clock::Emmc)] {
+    match (mailbox.clock_rate(clock), mailbox.max_clock_rate(clock)) {
+      (Ok(rate), Ok(max)) => println!("  {:<5} clock:       {} MHz (max {} MHz)", name, rate / 1_000_000, max / 1_000_000),
+      (Err(e), _) | (_, Err(e)) => println!("  {:<5} clock:       {}", name, e),
+    }
+  }
+
+  match (mailbox.temperature(), mailbox.max_temperature()) {
+    (Ok(t), Ok(max)) => println!("  Temperature:       {}.{} C (max {}.{} C)", t / 1000, t % 1000 / 100, max / 1000, max % 1000 / 100),
+    (Err(e), _) | (_, Err(e)) => println!("  Temperature:       {}", e),
+  }
+}
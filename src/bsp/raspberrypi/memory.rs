@@ -53,19 +53,30 @@ pub(super) mod map {
   /// However, making this tradeoff has the downside of making it possible for the CPU toassert a physical address that is not backed by any DRAM
   /// (ie: accessing an address close to 4GiB on an RPi3 that comes with only 1GiB of RAM)
   /// This would result in a crash or other kind of error
-  pub const END_INCLUSIVE: usize = 0xFFFF_FFFF;
-  pub const GPIO_OFFSET:   usize = 0x0020_0000;
-  pub const UART_OFFSET:   usize = 0x0020_1000;
+  pub const END_INCLUSIVE:  usize = 0xFFFF_FFFF;
+  pub const MAILBOX_OFFSET: usize = 0x0000_B880;
+  pub const PM_OFFSET:      usize = 0x0010_0000;
+  pub const RNG_OFFSET:     usize = 0x0010_4000;
+  pub const GPIO_OFFSET:    usize = 0x0020_0000;
+  pub const UART_OFFSET:    usize = 0x0020_1000;
+  pub const AUX_OFFSET:     usize = 0x0021_5000;
 
   /// Physical devices
   #[cfg(feature = "bsp_rpi3")]
   pub mod mmio {
     use super::*;
 
-    pub const START:            usize = 0x3F00_0000;
-    pub const GPIO_START:       usize = START + GPIO_OFFSET;
-    pub const PL011_UART_START: usize = START + UART_OFFSET;
-    pub const END_INCLUSIVE:    usize = 0x4000_FFFF;
+    pub const START:               usize = 0x3F00_0000;
+    pub const PERIPHERAL_IC_START: usize = START + 0x0000_B200;
+    pub const MAILBOX_START:       usize = START + MAILBOX_OFFSET;
+    pub const PM_START:            usize = START + PM_OFFSET;
+    pub const RNG_START:           usize = START + RNG_OFFSET;
+    pub const GPIO_START:          usize = START + GPIO_OFFSET;
+    pub const PL011_UART_START:    usize = START + UART_OFFSET;
+    pub const MINI_UART_START:     usize = START + AUX_OFFSET;
+    pub const EMMC_START:          usize = START + 0x0030_0000;
+    pub const LOCAL_IC_START:      usize = 0x4000_0000;
+    pub const END_INCLUSIVE:       usize = 0x4000_FFFF;
   }
 
   /// Physical devices
@@ -74,8 +85,16 @@ pub(super) mod map {
     use super::*;
 
     pub const START:            usize = 0xFE00_0000;
+    pub const MAILBOX_START:    usize = START + MAILBOX_OFFSET;
+    pub const PM_START:         usize = START + PM_OFFSET;
+    pub const RNG_START:        usize = START + RNG_OFFSET;
     pub const GPIO_START:       usize = START + GPIO_OFFSET;
     pub const PL011_UART_START: usize = START + UART_OFFSET;
+    pub const MINI_UART_START:  usize = START + AUX_OFFSET;
+    /// EMMC2; the controller wired to the SD card slot
+    pub const EMMC_START:       usize = START + 0x0034_0000;
+    pub const GICD_START:       usize = 0xFF84_1000;
+    pub const GICC_START:       usize = 0xFF84_2000;
     pub const END_INCLUSIVE:    usize = 0xFF84_FFFF;
   }
 }
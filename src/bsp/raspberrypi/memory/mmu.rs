@@ -4,15 +4,32 @@
 
 //! RPi Memory Management Unit
 
-use core::ops::RangeInclusive;
+use core::{
+  ops::RangeInclusive,
+  sync::atomic::{
+    AtomicBool,
+    AtomicUsize,
+    Ordering,
+  },
+};
 
 use super::map as memory_map;
-use crate::memory::mmu::*;
+use crate::{
+  bsp::raspberrypi::driver,
+  memory::mmu::*,
+};
 
 /// The kernel's address space defined by this BSP
 pub type KernelAddrSpace = AddressSpace<{ memory_map::END_INCLUSIVE + 1 }>;
 
-const NUM_MEM_RANGES: usize = 2;
+const NUM_MEM_RANGES: usize = 3;
+
+/// The VideoCore's share of DRAM as reported by the firmware; see `vc_memory_range_inclusive`
+///
+/// Only ever loaded and stored; atomic read-modify-write instructions don't work before the MMU is on
+static VC_MEMORY_QUERIED: AtomicBool  = AtomicBool::new(false);
+static VC_MEMORY_START:   AtomicUsize = AtomicUsize::new(0);
+static VC_MEMORY_SIZE:    AtomicUsize = AtomicUsize::new(0);
 
 /// The virtual memory layout
 /// The layout must contain only special ranges - meaning only things _not_ normal cacheable DRAM
@@ -40,6 +57,16 @@ pub static LAYOUT: KernelVirtualLayout<NUM_MEM_RANGES> = KernelVirtualLayout::ne
         execute_never: true,
       },
     },
+    TranslationDescriptor {
+      name: "VideoCore memory (framebuffer)",
+      virtual_range: vc_memory_range_inclusive,
+      physical_range_translation: Translation::Identity,
+      attribute_fields: AttributeFields {
+        mem_attributes: MemAttributes::NonCacheableDRAM,
+        acc_perms: AccessPermissions::ReadWrite,
+        execute_never: true,
+      },
+    },
   ]
 );
 
@@ -52,5 +79,27 @@ fn mmio_range_inclusive() -> RangeInclusive<usize> {
   RangeInclusive::new(memory_map::mmio::START, memory_map::mmio::END_INCLUSIVE)
 }
 
+/// The VideoCore's share of DRAM; the framebuffer is allocated from it
+///
+/// Its size depends on `gpu_mem` in config.txt; so the firmware is asked, once, when the translation tables are built
+/// The mailbox works before the MMU is on; if the firmware can't tell, the range is empty and nothing is mapped
+pub fn vc_memory_range_inclusive() -> RangeInclusive<usize> {
+  if !VC_MEMORY_QUERIED.load(Ordering::Relaxed) {
+    let (start, size) = driver::mailbox().vc_memory().unwrap_or((0, 0));
+
+    VC_MEMORY_START.store(start, Ordering::Relaxed);
+    VC_MEMORY_SIZE.store(size, Ordering::Relaxed);
+    VC_MEMORY_QUERIED.store(true, Ordering::Relaxed);
+  }
+
+  let start = VC_MEMORY_START.load(Ordering::Relaxed);
+  let size = VC_MEMORY_SIZE.load(Ordering::Relaxed);
+
+  match size {
+    0 => RangeInclusive::new(1, 0),
+    _ => RangeInclusive::new(start, start + size - 1),
+  }
+}
+
 /// Get a reference to the virtual memory layout
 pub fn virt_mem_layout() -> &'static KernelVirtualLayout<NUM_MEM_RANGES> { &LAYOUT }
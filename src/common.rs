@@ -4,6 +4,8 @@
 
 //! General purpose code
 
+use core::fmt;
+
 /// Convert a size into a human-readable format
 pub const fn size_human_readable_ceil(size: usize) -> (usize, &'static str) {
   const KIB: usize = 1024;
@@ -14,4 +16,84 @@ pub const fn size_human_readable_ceil(size: usize) -> (usize, &'static str) {
   else if (size / MIB) > 0 { (size.div_ceil(GIB), "MiB" ) }
   else if (size / KIB) > 0 { (size.div_ceil(GIB), "KiB" ) }
   else                     { (size,               "Byte") }
-}
\ No newline at end of file
+}
+
+/// A `usize` with an inclusive upper bound; checked on creation
+#[derive(Copy, Clone)]
+pub struct BoundedUsize<const MAX_INCLUSIVE: usize>(usize);
+
+impl<const MAX_INCLUSIVE: usize> BoundedUsize<{ MAX_INCLUSIVE }> {
+  /// Create an instance
+  pub const fn new(number: usize) -> Self {
+    assert!(number <= MAX_INCLUSIVE);
+
+    Self(number)
+  }
+
+  /// The wrapped number
+  pub const fn get(self) -> usize {
+    self.0
+  }
+}
+
+impl<const MAX_INCLUSIVE: usize> fmt::Display for BoundedUsize<{ MAX_INCLUSIVE }> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(&self.0, f)
+  }
+}
+
+/// A string stored inline with room for `CAPACITY` bytes; for names made up at runtime
+#[derive(Copy, Clone)]
+pub struct FixedString<const CAPACITY: usize> {
+  bytes: [u8; CAPACITY],
+  len: usize,
+}
+
+impl<const CAPACITY: usize> FixedString<{ CAPACITY }> {
+  /// Create an empty instance
+  pub const fn new() -> Self {
+    Self { bytes: [0; CAPACITY], len: 0 }
+  }
+
+  /// Create an instance holding `s`; fails if it doesn't fit
+  pub fn try_from_str(s: &str) -> Result<Self, &'static str> {
+    let mut string = Self::new();
+
+    string.push_str(s)?;
+
+    Ok(string)
+  }
+
+  /// Append `s`; fails and leaves the string as it was if it doesn't fit
+  pub fn push_str(&mut self, s: &str) -> Result<(), &'static str> {
+    let dst = self.bytes.get_mut(self.len..self.len + s.len()).ok_or("String too long")?;
+
+    dst.copy_from_slice(s.as_bytes());
+    self.len += s.len();
+
+    Ok(())
+  }
+
+  /// Shorten to `len` bytes; which must be on a `char` boundary
+  pub fn truncate(&mut self, len: usize) {
+    if len < self.len && self.as_str().is_char_boundary(len) { self.len = len; }
+  }
+
+  /// The string as a string slice
+  pub fn as_str(&self) -> &str {
+    // Only whole `str`s are ever copied in
+    core::str::from_utf8(&self.bytes[..self.len]).unwrap_or("")
+  }
+}
+
+impl<const CAPACITY: usize> fmt::Write for FixedString<{ CAPACITY }> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.push_str(s).map_err(|_| fmt::Error)
+  }
+}
+
+impl<const CAPACITY: usize> fmt::Display for FixedString<{ CAPACITY }> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
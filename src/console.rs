@@ -3,16 +3,51 @@
 // Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
 
 //! System console
+//!
+//! Output fans out to all registered sinks; input comes from a single selectable source
 
+pub mod ansi;
+pub mod font;
+
+mod line_discipline;
+mod mux;
 mod null_console;
 
-use crate::synchronization::{
-  NullLock,
-  self,
+use core::{
+  fmt,
+  future::poll_fn,
+  task::{
+    Poll,
+    Waker,
+  },
+};
+
+use crate::{
+  info,
+  log::{
+    self,
+    Level,
+  },
+  synchronization::{
+    IRQSafeNullLock,
+    self,
+  },
+};
+
+pub use line_discipline::{
+  LineDiscipline,
+  Mode,
+  ReadLineError,
 };
+pub use mux::Sink;
+
+use mux::ConsoleMux;
 
 pub mod interface {
-  use core::fmt;
+  use core::{
+    fmt,
+    task::Waker,
+  };
 
   /// Console write functions
   pub trait Write {
@@ -22,8 +57,11 @@ pub mod interface {
     /// Write a Rust format string
     fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result;
 
-    // Block until the last buffered character has been physically put on the TX wire
-    // fn flush(&self);
+    /// Block until the last buffered character has been physically put on the TX wire
+    fn flush(&self);
+
+    /// Whether ANSI escape sequences are understood; if not, they are stripped before reaching this writer
+    fn supports_ansi(&self) -> bool { false }
   }
 
   /// Console read functions
@@ -33,33 +71,200 @@ pub mod interface {
       ' '
     }
 
+    /// Read a single character if one has arrived; without waiting
+    fn try_read_char(&self) -> Option<char> { None }
+
+    /// Have `waker` woken once a character may have arrived; `false` if the device can't tell
+    fn wake_on_rx(&self, _waker: &Waker) -> bool { false }
+
     /// Clear the RX buffer
     fn clear_rx(&self);
   }
 
   /// Super-fun console statistics!
   pub trait Statistics {
-    // The number of characters written
-    // fn chars_written(&self) -> usize { 0 }
+    /// The number of characters written
+    fn chars_written(&self) -> usize { 0 }
 
-    // Why is this here if it isn't used?
-    // This caused hours of frustration because rustc was throwing dead code warning - which this project treats like errors
-    // The number of character read
-    // fn chars_read(&self) -> usize { 0 }
+    /// The number of characters read
+    fn chars_read(&self) -> usize { 0 }
+
+    /// The number of times received data was lost because the RX FIFO was full
+    fn rx_overruns(&self) -> usize { 0 }
+
+    /// The number of characters received without a valid stop bit
+    fn framing_errors(&self) -> usize { 0 }
+
+    /// The number of characters received with a parity mismatch
+    fn parity_errors(&self) -> usize { 0 }
   }
 
   pub trait All: Write + Read + Statistics {}
 }
 
-static CUR_CONSOLE: NullLock<&'static (dyn interface::All + Sync)> = NullLock::new(&null_console::NULL_CONSOLE);
+/// The console handed out by `console()`; forwards to the multiplexer
+struct Console;
+
+/// IRQ handlers log too; and must not get to the multiplexer while a write is in progress
+static MUX: IRQSafeNullLock<ConsoleMux> = IRQSafeNullLock::new(ConsoleMux::new());
+
+static CONSOLE: Console = Console;
+
+static LINE_DISCIPLINE: LineDiscipline = LineDiscipline::new();
 
 use synchronization::interface::Mutex;
 
-/// Register the console
-pub fn register_console(new_console: &'static (dyn interface::All + Sync)) {
-  CUR_CONSOLE.lock(|c| *c = new_console);
+impl interface::Write for Console {
+  fn write_char(&self, c: char) {
+    MUX.lock(|m| m.for_each_sink(|s| s.writer.write_char(c)));
+  }
+
+  fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result {
+    MUX.lock(|m| m.write_fmt(args))
+  }
+
+  fn flush(&self) {
+    MUX.lock(|m| m.for_each_sink(|s| s.writer.flush()));
+  }
+}
+
+impl interface::Read for Console {
+  fn read_char(&self) -> char {
+    MUX.lock(|m| m.input()).read_char()
+  }
+
+  fn try_read_char(&self) -> Option<char> {
+    MUX.lock(|m| m.input()).try_read_char()
+  }
+
+  fn wake_on_rx(&self, waker: &Waker) -> bool {
+    MUX.lock(|m| m.input()).wake_on_rx(waker)
+  }
+
+  fn clear_rx(&self) {
+    MUX.lock(|m| m.input()).clear_rx()
+  }
+}
+
+/// Statistics of the input source
+impl interface::Statistics for Console {
+  fn chars_written(&self) -> usize {
+    MUX.lock(|m| m.input()).chars_written()
+  }
+
+  fn chars_read(&self) -> usize {
+    MUX.lock(|m| m.input()).chars_read()
+  }
+
+  fn rx_overruns(&self) -> usize {
+    MUX.lock(|m| m.input()).rx_overruns()
+  }
+
+  fn framing_errors(&self) -> usize {
+    MUX.lock(|m| m.input()).framing_errors()
+  }
+
+  fn parity_errors(&self) -> usize {
+    MUX.lock(|m| m.input()).parity_errors()
+  }
+}
+impl interface::All for Console {}
+
+/// Register a console as both an output sink and the input source
+///
+/// Records logged before the first console was registered are replayed to it
+pub fn register_console(name: &'static str, new_console: &'static (dyn interface::All + Sync)) -> Result<(), &'static str> {
+  add_sink(name, new_console, Level::Trace)?;
+  set_input(new_console);
+
+  Ok(())
+}
+
+/// Add an output sink receiving log records up to `level`
+///
+/// Records logged before the first sink was added are replayed to it
+pub fn add_sink(
+  name: &'static str,
+  writer: &'static (dyn interface::Write + Sync),
+  level: Level,
+) -> Result<(), &'static str> {
+  MUX.lock(|m| m.add_sink(Sink { name, writer, level }))?;
+
+  log::KERNEL_LOG.replay_early_records(writer);
+
+  Ok(())
+}
+
+/// Remove the output sink called `name`
+#[allow(dead_code)]
+pub fn remove_sink(name: &str) -> Result<(), &'static str> {
+  MUX.lock(|m| m.remove_sink(name))
+}
+
+/// Change the log level of the output sink called `name`
+#[allow(dead_code)]
+pub fn set_sink_level(name: &str, level: Level) -> Result<(), &'static str> {
+  MUX.lock(|m| m.set_sink_level(name, level))
+}
+
+/// Select the input source
+pub fn set_input(input: &'static (dyn interface::All + Sync)) {
+  MUX.lock(|m| m.set_input(input));
+}
+
+/// Call `f` for every registered output sink
+pub fn for_each_sink(f: impl FnMut(&Sink)) {
+  MUX.lock(|m| m.for_each_sink(f));
+}
+
+/// Write a log record of `level` to every sink that admits it
+pub fn write_record(level: Level, args: fmt::Arguments) -> fmt::Result {
+  MUX.lock(|m| m.write_record(level, args))
 }
 
+/// The system console; writes fan out to all sinks, reads come from the input source
 pub fn console() -> &'static dyn interface::All {
-  CUR_CONSOLE.lock(|c| *c)
-}
\ No newline at end of file
+  &CONSOLE
+}
+
+/// The input source
+pub fn input() -> &'static dyn interface::All {
+  MUX.lock(|m| m.input())
+}
+
+/// Wait for a character from the input source without blocking; the async `interface::Read::read_char`
+///
+/// An input source that can't wake the task on RX is polled on every round of the executor
+pub async fn read_char() -> char {
+  poll_fn(|cx| {
+    let input = input();
+
+    if let Some(c) = input.try_read_char() { return Poll::Ready(c); }
+
+    if !input.wake_on_rx(cx.waker()) { cx.waker().wake_by_ref(); }
+
+    // A character that arrived before the wake-up was armed doesn't wake it; so check again
+    input.try_read_char().map_or(Poll::Pending, Poll::Ready)
+  }).await
+}
+
+/// The line discipline applied by `read_line`
+pub fn line_discipline() -> &'static LineDiscipline {
+  &LINE_DISCIPLINE
+}
+
+/// Read a line from the input source through the line discipline
+pub fn read_line(buf: &mut [u8]) -> Result<&str, ReadLineError> {
+  LINE_DISCIPLINE.read_line(input(), buf)
+}
+
+/// Print the console's statistics
+pub fn print_statistics() {
+  let c = console();
+
+  info!("\tChars written:  {}", c.chars_written());
+  info!("\tChars read:     {}", c.chars_read());
+  info!("\tRX overruns:    {}", c.rx_overruns());
+  info!("\tFraming errors: {}", c.framing_errors());
+  info!("\tParity errors:  {}", c.parity_errors());
+}
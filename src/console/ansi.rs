@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! ANSI/VT100 escape sequences
+//!
+//! Escape sequences are plain `Display` values; mix them into any format string
+//! Sinks that don't support them (`console::interface::Write::supports_ansi`) get the text with all escape sequences stripped
+
+use core::fmt;
+
+use super::interface;
+
+const ESC: char = '\x1b';
+
+/// Reset all attributes
+pub const RESET: &str = "\x1b[0m";
+
+/// Bold (or bright, depending on the terminal) text
+pub const BOLD: &str = "\x1b[1m";
+
+/// Clear the whole screen; the cursor position is unchanged
+pub const CLEAR_SCREEN: &str = "\x1b[2J";
+
+/// The eight standard terminal colours
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub enum Color {
+  Black,
+  Red,
+  Green,
+  Yellow,
+  Blue,
+  Magenta,
+  Cyan,
+  White,
+}
+
+/// Set the foreground colour
+pub struct Fg(pub Color);
+
+/// Set the background colour
+#[allow(dead_code)]
+pub struct Bg(pub Color);
+
+/// Move the cursor; `row` and `col` start at 1
+pub struct CursorTo {
+  pub row: usize,
+  pub col: usize,
+}
+
+impl fmt::Display for Fg {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}[3{}m", ESC, self.0 as u8)
+  }
+}
+
+impl fmt::Display for Bg {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}[4{}m", ESC, self.0 as u8)
+  }
+}
+
+impl fmt::Display for CursorTo {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}[{};{}H", ESC, self.row, self.col)
+  }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum StripState {
+  Text,
+  Escape,
+  ControlSequence,
+}
+
+/// Forwards text to a console writer with all escape sequences removed
+///
+/// Handles `ESC [ <parameters> <final byte>` sequences; and drops the character following any other `ESC`
+pub struct AnsiStripper<'a> {
+  writer: &'a dyn interface::Write,
+  state: StripState,
+}
+
+impl<'a> AnsiStripper<'a> {
+  /// Create an instance
+  pub fn new(writer: &'a dyn interface::Write) -> Self {
+    Self {
+      writer,
+      state: StripState::Text,
+    }
+  }
+}
+
+impl fmt::Write for AnsiStripper<'_> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    // Start of the current run of plain text
+    let mut run_start = 0;
+
+    for (i, c) in s.char_indices() {
+      match (self.state, c) {
+        (StripState::Text, ESC) => {
+          if run_start < i { self.writer.write_fmt(format_args!("{}", &s[run_start..i]))?; }
+
+          self.state = StripState::Escape;
+        }
+        (StripState::Text, _) => continue,
+        (StripState::Escape, '[') => self.state = StripState::ControlSequence,
+        (StripState::Escape, _) => self.state = StripState::Text,
+        // Parameter and intermediate bytes; the final byte is in '@'..='~'
+        (StripState::ControlSequence, '@'..='~') => self.state = StripState::Text,
+        (StripState::ControlSequence, _) => {}
+      }
+
+      run_start = i + c.len_utf8();
+    }
+
+    if self.state == StripState::Text && run_start < s.len() {
+      self.writer.write_fmt(format_args!("{}", &s[run_start..]))?;
+    }
+
+    Ok(())
+  }
+}
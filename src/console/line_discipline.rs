@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Line discipline
+//!
+//! Sits between the console's input source and its readers
+//! In canonical mode input is collected and edited line by line:
+//! - Carriage return ends the line, like line feed
+//! - Backspace/DEL erases the last character
+//! - Ctrl-U erases the whole line
+//! - Ctrl-C abandons the line
+//!
+//! In raw mode characters are passed through unprocessed; only a line feed (Ctrl-J) ends the line
+
+use core::fmt;
+
+use super::interface;
+use crate::synchronization::{
+  interface::Mutex,
+  NullLock,
+};
+
+const BACKSPACE: char = '\x08';
+const DELETE:    char = '\x7f';
+const CTRL_C:    char = '\x03';
+const CTRL_U:    char = '\x15';
+
+/// Input processing modes
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Mode {
+  /// Line editing; lines are handed out once complete
+  Canonical,
+
+  /// No processing at all
+  Raw,
+}
+
+/// Read line error variants
+#[derive(Debug)]
+pub enum ReadLineError {
+  /// The line was abandoned with Ctrl-C
+  Interrupted,
+}
+
+struct LineDisciplineInner {
+  mode: Mode,
+  echo: bool,
+}
+
+pub struct LineDiscipline {
+  inner: NullLock<LineDisciplineInner>,
+}
+
+unsafe impl Sync for LineDiscipline {}
+
+impl fmt::Display for ReadLineError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ReadLineError::Interrupted => write!(f, "Interrupted"),
+    }
+  }
+}
+
+impl LineDiscipline {
+  /// Create an instance; canonical mode with echo enabled
+  pub const fn new() -> Self {
+    Self {
+      inner: NullLock::new(LineDisciplineInner {
+        mode: Mode::Canonical,
+        echo: true,
+      }),
+    }
+  }
+
+  /// Select the input processing mode
+  pub fn set_mode(&self, mode: Mode) {
+    self.inner.lock(|i| i.mode = mode);
+  }
+
+  /// The input processing mode
+  pub fn mode(&self) -> Mode {
+    self.inner.lock(|i| i.mode)
+  }
+
+  /// Enable or disable echoing input back to the input source
+  pub fn set_echo(&self, echo: bool) {
+    self.inner.lock(|i| i.echo = echo);
+  }
+
+  /// Whether input is echoed back to the input source
+  pub fn echo(&self) -> bool {
+    self.inner.lock(|i| i.echo)
+  }
+
+  /// Read a line into `buf` and return it; without the trailing newline
+  ///
+  /// Only ASCII is stored; other characters are dropped, as are characters that don't fit into `buf`
+  pub fn read_line<'a>(&self, input: &dyn interface::All, buf: &'a mut [u8]) -> Result<&'a str, ReadLineError> {
+    let canonical = self.mode() == Mode::Canonical;
+    let echo = self.echo();
+    let mut len = 0;
+
+    loop {
+      let c = match input.read_char() {
+        // Terminals send a carriage return for Enter
+        '\r' if canonical => '\n',
+        c                 => c,
+      };
+
+      match c {
+        '\n' => {
+          if echo { input.write_char('\n'); }
+
+          break;
+        }
+
+        BACKSPACE | DELETE if canonical => {
+          if len > 0 {
+            len -= 1;
+
+            if echo { input.write_fmt(format_args!("{0} {0}", BACKSPACE)).unwrap(); }
+          }
+        }
+
+        CTRL_U if canonical => {
+          if echo {
+            for _ in 0..len { input.write_fmt(format_args!("{0} {0}", BACKSPACE)).unwrap(); }
+          }
+
+          len = 0;
+        }
+
+        CTRL_C if canonical => {
+          if echo { input.write_fmt(format_args!("^C\n")).unwrap(); }
+
+          return Err(ReadLineError::Interrupted);
+        }
+
+        // Canonical mode only takes printable characters; raw mode takes anything ASCII
+        c if c.is_ascii() && (!canonical || !c.is_ascii_control()) && len < buf.len() => {
+          buf[len] = c as u8;
+          len += 1;
+
+          if echo { input.write_char(c); }
+        }
+
+        _ => {}
+      }
+    }
+
+    // Only ASCII is ever stored in `buf`
+    Ok(unsafe { core::str::from_utf8_unchecked(&buf[..len]) })
+  }
+}
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Console multiplexer
+//!
+//! Fans writes out to all registered sinks and reads from a single input source
+
+use core::fmt;
+
+use super::{
+  ansi::AnsiStripper,
+  interface,
+  null_console,
+};
+use crate::log::{
+  self,
+  Level,
+};
+
+const NUM_SINKS: usize = 4;
+
+/// An output registered with the multiplexer
+#[derive(Copy, Clone)]
+pub struct Sink {
+  /// Identifies the sink when changing or removing it
+  pub name: &'static str,
+
+  /// Where the output goes
+  pub writer: &'static (dyn interface::Write + Sync),
+
+  /// The most verbose log level written to this sink
+  pub level: Level,
+}
+
+impl Sink {
+  /// Write to the sink; stripping escape sequences if it doesn't support them
+  fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result {
+    if self.writer.supports_ansi() { return self.writer.write_fmt(args); }
+
+    fmt::Write::write_fmt(&mut AnsiStripper::new(self.writer), args)
+  }
+}
+
+pub struct ConsoleMux {
+  sinks: [Option<Sink>; NUM_SINKS],
+  input: &'static (dyn interface::All + Sync),
+}
+
+impl ConsoleMux {
+  /// Create an instance
+  ///
+  /// The kernel log is always registered; so records are captured from the very first instruction
+  pub const fn new() -> Self {
+    let mut sinks = [None; NUM_SINKS];
+    sinks[0] = Some(Sink {
+      name: log::KERNEL_LOG_SINK_NAME,
+      writer: &log::KERNEL_LOG,
+      level: Level::Trace,
+    });
+
+    Self {
+      sinks,
+      input: &null_console::NULL_CONSOLE,
+    }
+  }
+
+  fn find(&mut self, name: &str) -> Option<&mut Sink> {
+    self.sinks.iter_mut().flatten().find(|s| s.name == name)
+  }
+
+  /// Register a new sink
+  pub fn add_sink(&mut self, sink: Sink) -> Result<(), &'static str> {
+    if self.find(sink.name).is_some() { return Err("Sink already registered"); }
+
+    match self.sinks.iter_mut().find(|s| s.is_none()) {
+      Some(free) => {
+        *free = Some(sink);
+
+        Ok(())
+      }
+      None => Err("No free sink slots"),
+    }
+  }
+
+  /// Unregister the sink called `name`
+  pub fn remove_sink(&mut self, name: &str) -> Result<(), &'static str> {
+    match self.sinks.iter_mut().find(|s| matches!(s, Some(s) if s.name == name)) {
+      Some(slot) => {
+        *slot = None;
+
+        Ok(())
+      }
+      None => Err("No such sink"),
+    }
+  }
+
+  /// Change the log level of the sink called `name`
+  pub fn set_sink_level(&mut self, name: &str, level: Level) -> Result<(), &'static str> {
+    match self.find(name) {
+      Some(sink) => {
+        sink.level = level;
+
+        Ok(())
+      }
+      None => Err("No such sink"),
+    }
+  }
+
+  /// Select the input source
+  pub fn set_input(&mut self, input: &'static (dyn interface::All + Sync)) {
+    self.input = input;
+  }
+
+  /// The input source
+  pub fn input(&self) -> &'static (dyn interface::All + Sync) {
+    self.input
+  }
+
+  /// Call `f` for every registered sink
+  pub fn for_each_sink(&self, f: impl FnMut(&Sink)) {
+    self.sinks.iter().flatten().for_each(f)
+  }
+
+  /// Write to every sink; regardless of its level
+  pub fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result {
+    self.sinks.iter().flatten().try_for_each(|s| s.write_fmt(args))
+  }
+
+  /// Write to every sink whose level admits `level`
+  pub fn write_record(&self, level: Level, args: fmt::Arguments) -> fmt::Result {
+    self.
+      sinks.
+      iter().
+      flatten().
+      filter(|s| level <= s.level).
+      try_for_each(|s| s.write_fmt(args))
+  }
+}
@@ -10,6 +10,9 @@ mod arch_cpu;
 mod boot;
 
 pub use arch_cpu::{
+  clean_invalidate_dcache_range,
+  invalidate_dcache_range,
   nop,
+  wait_for_interrupt,
   wait_forever,
 };
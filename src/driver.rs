@@ -4,15 +4,21 @@
 
 //! OS driver support
 
+use core::fmt;
+
 use crate::{
-  info,
+  debug,
+  log::{
+    Level,
+    LineLogger,
+  },
   synchronization::{
     interface::Mutex,
     NullLock,
   },
 };
 
-const NUM_DRIVERS: usize = 5;
+const NUM_DRIVERS: usize = 12;
 
 struct DriverManagerInner {
   next_index: usize,
@@ -111,6 +117,8 @@ impl DriverManager {
   /// - During init, drivers might to things with system-wide impact
   pub fn init_drivers(&self) {
     self.for_each_descriptor(|d| {
+      debug!("Initializing {} driver", d.device_driver.compatible());
+
       // 1. Initialize the driver
       if let Err(e) = unsafe { d.device_driver.init() } {
         panic!("Error initializing {} driver: {}", d.device_driver.compatible(), e);
@@ -126,11 +134,19 @@ impl DriverManager {
   }
 
   pub fn enumerate(&self) {
+    let _ = self.write_list(&mut LineLogger::new(Level::Info, module_path!(), "\t"));
+  }
+
+  /// Write the numbered list of drivers to `w`; one per line
+  pub fn write_list(&self, w: &mut dyn fmt::Write) -> fmt::Result {
     let mut i: usize = 1;
-  
-      self.for_each_descriptor(|d| {
-        info!("\t{}: {}", i, d.device_driver.compatible());
-        i += 1;
-      });
+    let mut result = Ok(());
+
+    self.for_each_descriptor(|d| {
+      result = result.and_then(|_| writeln!(w, "{}: {}", i, d.device_driver.compatible()));
+      i += 1;
+    });
+
+    result
   }
 }
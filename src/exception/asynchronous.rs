@@ -3,9 +3,141 @@
 // Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
 
 //! Asynchronous exception handling
+//!
+//! IRQs are dispatched by the BSP's interrupt controller driver; which registers itself as the IRQ manager
+//! Until it does, the null IRQ manager is in place and no IRQ can be enabled
 
 #[cfg(target_arch = "aarch64")]
 #[path = "../_arch/aarch64/exception/asynchronous.rs"]
 mod arch_asynchronous;
+mod null_irq_manager;
 
-pub use arch_asynchronous::print_state;
\ No newline at end of file
+use core::fmt;
+
+use crate::{
+  bsp,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+};
+
+pub use arch_asynchronous::{
+  local_irq_mask_save,
+  local_irq_restore,
+  local_irq_unmask,
+  print_state,
+  write_state,
+};
+
+/// The board's interrupt number type
+pub type IRQNumber = bsp::exception::asynchronous::IRQNumber;
+
+/// Interrupt descriptor
+#[derive(Copy, Clone)]
+pub struct IRQHandlerDescriptor<T>
+where T: Copy {
+  /// The IRQ number
+  number: T,
+
+  /// Descriptive name
+  name: &'static str,
+
+  /// Reference to handler trait object
+  handler: &'static (dyn interface::IRQHandler + Sync),
+}
+
+pub mod interface {
+  use super::IRQHandlerDescriptor;
+
+  /// Implemented by types that handle IRQs
+  pub trait IRQHandler {
+    /// Called when the corresponding interrupt is asserted
+    fn handle(&self) -> Result<(), &'static str>;
+  }
+
+  /// IRQ management functions
+  ///
+  /// The `BSP` is supposed to supply one global instance
+  /// Typically implemented by the platform's interrupt controller
+  pub trait IRQManager {
+    /// The IRQ number type depends on the implementation
+    type IRQNumberType: Copy;
+
+    /// Register a handler
+    fn register_handler(
+      &self,
+      descriptor: IRQHandlerDescriptor<Self::IRQNumberType>,
+    ) -> Result<(), &'static str>;
+
+    /// Enable an interrupt in the controller
+    fn enable(&self, irq_number: &Self::IRQNumberType);
+
+    /// Handle pending interrupts
+    ///
+    /// This function is called directly from the CPU's IRQ exception vector
+    /// On AArch64 this means that the respective CPU core has disabled exception signaling on the executing core
+    fn handle_pending_irqs(&self);
+
+    /// Print list of registered handlers
+    fn print_handler(&self) {}
+  }
+}
+
+static CUR_IRQ_MANAGER: NullLock<&'static (dyn interface::IRQManager<IRQNumberType = IRQNumber> + Sync)> =
+  NullLock::new(&null_irq_manager::NULL_IRQ_MANAGER);
+
+impl<T> IRQHandlerDescriptor<T>
+where T: Copy {
+  /// Create an instance
+  pub const fn new(
+    number: T,
+    name: &'static str,
+    handler: &'static (dyn interface::IRQHandler + Sync),
+  ) -> Self {
+    Self {
+      number,
+      name,
+      handler,
+    }
+  }
+
+  /// The IRQ number
+  pub const fn number(&self) -> T {
+    self.number
+  }
+
+  /// The handler
+  pub const fn handler(&self) -> &'static (dyn interface::IRQHandler + Sync) {
+    self.handler
+  }
+}
+
+impl<T> fmt::Display for IRQHandlerDescriptor<T>
+where T: Copy + fmt::Display {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:>4}: {}", self.number, self.name)
+  }
+}
+
+/// Execute the provided closure while IRQs are masked on the executing core
+///
+/// While the function temporarily changes the HW state of the executing core, it restores it to the previous state before returning; so this is deemed safe
+#[inline(always)]
+pub fn exec_with_irq_masked<T>(f: impl FnOnce() -> T) -> T {
+  let saved = local_irq_mask_save();
+  let ret = f();
+  local_irq_restore(saved);
+
+  ret
+}
+
+/// Register a new IRQ manager
+pub fn register_irq_manager(new_manager: &'static (dyn interface::IRQManager<IRQNumberType = IRQNumber> + Sync)) {
+  CUR_IRQ_MANAGER.lock(|manager| *manager = new_manager);
+}
+
+/// Return a reference to the currently registered IRQ manager
+pub fn irq_manager() -> &'static (dyn interface::IRQManager<IRQNumberType = IRQNumber> + Sync) {
+  CUR_IRQ_MANAGER.lock(|manager| *manager)
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! An IRQ manager that manages nothing
+
+use super::{
+  interface,
+  IRQHandlerDescriptor,
+  IRQNumber,
+};
+
+pub struct NullIRQManager;
+
+pub static NULL_IRQ_MANAGER: NullIRQManager = NullIRQManager {};
+
+impl interface::IRQManager for NullIRQManager {
+  type IRQNumberType = IRQNumber;
+
+  fn register_handler(
+    &self,
+    _descriptor: IRQHandlerDescriptor<Self::IRQNumberType>,
+  ) -> Result<(), &'static str> {
+    Err("No IRQ manager registered yet")
+  }
+
+  fn enable(&self, _irq_number: &Self::IRQNumberType) {
+    panic!("No IRQ manager registered yet");
+  }
+
+  fn handle_pending_irqs(&self) {
+    panic!("No IRQ manager registered yet");
+  }
+}
+
+unsafe impl Sync for NullIRQManager {}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Async executor
+//!
+//! A lighter alternative to threads: a task is a future, and any number of them share the executor's one thread
+//! Futures live in a static pool of fixed-size slots; a future is polled in place, so it never moves once spawned
+//!
+//! A task is only polled again once it is woken; through the `Waker` it was last polled with
+//! Wakers are plain slot numbers; waking sets the slot's ready bit, which is fine from IRQ handlers too
+//! While no task is ready the executor lets the other threads run; and waits for an interrupt once it gets the core back
+
+mod commands;
+
+use core::{
+  cell::SyncUnsafeCell,
+  future::Future,
+  mem::MaybeUninit,
+  pin::Pin,
+  sync::atomic::{
+    AtomicU32,
+    Ordering,
+  },
+  task::{
+    Context,
+    Poll,
+    RawWaker,
+    RawWakerVTable,
+    Waker,
+  },
+};
+
+use crate::{
+  cpu,
+  exception::asynchronous::exec_with_irq_masked,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  shell,
+  thread,
+};
+
+/// At most 32; the ready bits are a `u32`
+const NUM_TASKS: usize = 16;
+
+/// The largest future a slot can hold; in bytes
+const TASK_SIZE:  usize = 512;
+const TASK_ALIGN: usize = 16;
+
+/// Polls the future of type-erased type in a slot; and drops it once it is done
+type PollFn = unsafe fn(*mut u8, &mut Context<'_>) -> Poll<()>;
+
+/// Only ever used through pointers to the future in it
+#[allow(dead_code)]
+#[repr(C, align(16))]
+struct Storage([MaybeUninit<u8>; TASK_SIZE]);
+
+/// The poll function of every slot's future; `None` for free slots
+static TASKS: NullLock<[Option<PollFn>; NUM_TASKS]> = NullLock::new([None; NUM_TASKS]);
+
+/// Only the executor touches a future once it is spawned; while its slot is in use
+static FUTURES: SyncUnsafeCell<[Storage; NUM_TASKS]> =
+  SyncUnsafeCell::new([const { Storage([MaybeUninit::uninit(); TASK_SIZE]) }; NUM_TASKS]);
+
+/// One bit per slot; set by waking, cleared before polling
+static READY: AtomicU32 = AtomicU32::new(0);
+
+static WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(clone_waker, wake, wake, drop_waker);
+
+fn raw_waker(slot: usize) -> RawWaker {
+  RawWaker::new(slot as *const (), &WAKER_VTABLE)
+}
+
+unsafe fn clone_waker(data: *const ()) -> RawWaker {
+  raw_waker(data as usize)
+}
+
+unsafe fn wake(data: *const ()) {
+  READY.fetch_or(1 << data as usize, Ordering::Release);
+}
+
+unsafe fn drop_waker(_data: *const ()) {}
+
+fn slot_ptr(slot: usize) -> *mut u8 {
+  unsafe { (&raw mut (*FUTURES.get())[slot]).cast() }
+}
+
+/// Poll the `F` at `future`; dropping it once it is done
+///
+/// # Safety
+///
+/// - `future` must point to a live `F` that stays in place
+unsafe fn poll_task<F: Future<Output = ()>>(future: *mut u8, cx: &mut Context<'_>) -> Poll<()> {
+  let future = future.cast::<F>();
+  let poll = unsafe { Pin::new_unchecked(&mut *future) }.poll(cx);
+
+  if poll.is_ready() { unsafe { future.drop_in_place() }; }
+
+  poll
+}
+
+/// Start the executor in a thread of its own and register its shell commands
+pub fn init() -> Result<(), &'static str> {
+  thread::spawn("executor", || { run(); })?;
+
+  shell::register_commands(&commands::COMMANDS)
+}
+
+/// Add a task running `future`; it is polled first thing on the executor's next round
+///
+/// Futures larger than a slot don't compile
+pub fn spawn<F: Future<Output = ()> + 'static>(future: F) -> Result<(), &'static str> {
+  const {
+    assert!(size_of::<F>() <= TASK_SIZE, "Future too large for a task slot");
+    assert!(align_of::<F>() <= TASK_ALIGN, "Future too strictly aligned for a task slot");
+  }
+
+  let slot = TASKS.lock(|tasks| {
+    let slot = tasks.iter().position(Option::is_none).ok_or("No free task slots")?;
+
+    // The slot is free; so the executor doesn't touch it
+    unsafe { slot_ptr(slot).cast::<F>().write(future) };
+    tasks[slot] = Some(poll_task::<F> as PollFn);
+
+    Ok(slot)
+  })?;
+
+  READY.fetch_or(1 << slot, Ordering::Release);
+
+  Ok(())
+}
+
+/// Poll woken tasks forever; waiting for an interrupt while none is
+fn run() -> ! {
+  loop {
+    let mut ready = READY.swap(0, Ordering::Acquire);
+
+    while ready != 0 {
+      let slot = ready.trailing_zeros() as usize;
+      ready &= ready - 1;
+
+      // A stale waker may wake a slot whose task has finished
+      let Some(poll) = TASKS.lock(|tasks| tasks[slot]) else { continue; };
+
+      let waker = unsafe { Waker::from_raw(raw_waker(slot)) };
+      let mut cx = Context::from_waker(&waker);
+
+      // Outside the lock; the task may well spawn others
+      if unsafe { poll(slot_ptr(slot), &mut cx) }.is_ready() {
+        TASKS.lock(|tasks| tasks[slot] = None);
+      }
+    }
+
+    // Let other ready threads have the core first; then sleep until something happens
+    thread::yield_now();
+
+    // A pending IRQ ends `wfi` even while masked; so a wake-up between the check and `wfi` isn't missed
+    exec_with_irq_masked(|| {
+      if READY.load(Ordering::Acquire) == 0 { cpu::wait_for_interrupt(); }
+    });
+  }
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Async executor shell commands
+
+use core::{
+  future::{
+    Future,
+    poll_fn,
+  },
+  pin::pin,
+  str::SplitWhitespace,
+  task::Poll,
+  time::Duration,
+};
+
+use crate::{
+  console,
+  executor,
+  println,
+  shell::{
+    parse_number,
+    Command,
+  },
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  thread,
+  time,
+};
+
+pub const COMMANDS: [Command; 1] = [
+  Command::new("getkey",   "getkey <ms>: Wait for a key in an async task",      getkey),
+];
+
+/// What the `getkey` task got; `None` until it is done, then the key or `None` on timeout
+static GETKEY_RESULT: NullLock<Option<Option<char>>> = NullLock::new(None);
+
+/// The next character from the console; `None` if none arrives within `timeout`
+async fn read_char_timeout(timeout: Duration) -> Option<char> {
+  let mut key = pin!(console::read_char());
+  let mut timeout = pin!(time::time_manager().sleep(timeout));
+
+  poll_fn(|cx| {
+    if let Poll::Ready(c) = key.as_mut().poll(cx) { return Poll::Ready(Some(c)); }
+
+    timeout.as_mut().poll(cx).map(|()| None)
+  }).await
+}
+
+fn getkey(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let timeout = Duration::from_millis(parse_number(args.next())? as u64);
+
+  GETKEY_RESULT.lock(|result| *result = None);
+  executor::spawn(async move {
+    let key = read_char_timeout(timeout).await;
+
+    GETKEY_RESULT.lock(|result| *result = Some(key));
+  })?;
+
+  // The shell doesn't read the console meanwhile; so the task gets the key
+  let key = loop {
+    if let Some(key) = GETKEY_RESULT.lock(|result| *result) { break key; }
+
+    thread::sleep(time::TICK_INTERVAL);
+  };
+
+  match key {
+    Some(c) => println!("{:?}", c),
+    None    => println!("Timed out"),
+  }
+
+  Ok(())
+}
@@ -0,0 +1,447 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Virtual filesystem
+//!
+//! Filesystems are mounted at absolute paths; a path belongs to the mount with the longest path that is a prefix of it
+//! Until something is mounted at `/`, the root is an empty directory; mount points show up in their parent directory either way
+//!
+//! Paths are normalised lexically; so `/boot/../proc` is `/proc`
+//! Open files live in a global table; a file descriptor is an index into it
+
+mod commands;
+mod empty_dir;
+pub mod fat32;
+pub mod initramfs;
+pub mod procfs;
+
+use crate::{
+  block,
+  common::FixedString,
+  shell,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+};
+
+/// The longest file name in bytes
+pub const MAX_NAME_LEN: usize = 255;
+
+/// The longest path in bytes
+pub const MAX_PATH_LEN: usize = 128;
+
+const NUM_MOUNTS: usize = 8;
+const NUM_OPEN_FILES: usize = 32;
+
+pub mod interface {
+  use super::{
+    DirEntry,
+    InodeRef,
+    Stat,
+  };
+
+  /// A file or directory
+  ///
+  /// References come from `FileSystem::root` and `lookup`; each one is given back with `release`
+  pub trait Inode {
+    /// The type and size
+    fn stat(&self) -> Stat;
+
+    /// The entry named `name` in this directory
+    fn lookup(&'static self, name: &str) -> Result<InodeRef, &'static str>;
+
+    /// Read from byte `offset` into `buf`; returns the number of bytes read, 0 at the end of the file
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, &'static str>;
+
+    /// Write `buf` at byte `offset`; returns the number of bytes written
+    fn write_at(&self, _offset: u64, _buf: &[u8]) -> Result<usize, &'static str> {
+      Err("Read-only filesystem")
+    }
+
+    /// Call `f` for every entry of this directory; stop early once it returns `false`
+    fn read_dir(&self, f: &mut dyn FnMut(&DirEntry) -> bool) -> Result<(), &'static str>;
+
+    /// Give back a reference; for filesystems that recycle their inodes
+    fn release(&self) {}
+  }
+
+  /// A mountable filesystem
+  pub trait FileSystem {
+    /// The filesystem type; e.g. `fat32`
+    fn name(&self) -> &'static str;
+
+    /// The root directory
+    fn root(&'static self) -> Result<InodeRef, &'static str>;
+
+    /// Called when the filesystem is unmounted; there are no open files on it by then
+    fn unmount(&self) -> Result<(), &'static str> {
+      Ok(())
+    }
+  }
+}
+
+/// A reference to an inode
+pub type InodeRef = &'static (dyn interface::Inode + Sync);
+
+/// A reference to a filesystem
+pub type FileSystemRef = &'static (dyn interface::FileSystem + Sync);
+
+/// A file name
+pub type Name = FixedString<MAX_NAME_LEN>;
+
+/// A normalised absolute path
+pub type Path = FixedString<MAX_PATH_LEN>;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum FileType {
+  File,
+  Directory,
+}
+
+/// What `stat` reports
+#[derive(Copy, Clone)]
+pub struct Stat {
+  pub file_type: FileType,
+
+  /// The size in bytes; 0 for directories
+  pub size: u64,
+}
+
+/// A directory entry
+#[derive(Copy, Clone)]
+pub struct DirEntry {
+  pub name: Name,
+  pub stat: Stat,
+}
+
+/// Where `seek` moves to
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub enum SeekFrom {
+  Start(u64),
+  Current(i64),
+  End(i64),
+}
+
+/// An open file descriptor
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Fd(usize);
+
+#[derive(Copy, Clone)]
+struct Mount {
+  path: Path,
+  fs: FileSystemRef,
+}
+
+#[derive(Copy, Clone)]
+struct OpenFile {
+  path: Path,
+  inode: InodeRef,
+
+  /// `None` for the empty root directory
+  fs: Option<FileSystemRef>,
+  offset: u64,
+}
+
+struct VfsInner {
+  mounts: [Option<Mount>; NUM_MOUNTS],
+  files: [Option<OpenFile>; NUM_OPEN_FILES],
+}
+
+static VFS: NullLock<VfsInner> = NullLock::new(VfsInner {
+  mounts: [None; NUM_MOUNTS],
+  files: [None; NUM_OPEN_FILES],
+});
+
+impl Stat {
+  /// Whether this is a directory
+  pub fn is_dir(&self) -> bool {
+    self.file_type == FileType::Directory
+  }
+}
+
+impl DirEntry {
+  /// Create an instance; fails for names longer than `MAX_NAME_LEN`
+  pub fn new(name: &str, stat: Stat) -> Result<Self, &'static str> {
+    Ok(Self { name: Name::try_from_str(name)?, stat })
+  }
+}
+
+/// Normalise the absolute `path`; resolving `.` and `..` and dropping repeated and trailing slashes
+pub fn normalize(path: &str) -> Result<Path, &'static str> {
+  if !path.starts_with('/') { return Err("Path must be absolute"); }
+
+  let mut normalized = Path::new();
+
+  for component in path.split('/') {
+    match component {
+      "" | "." => (),
+      ".."     => normalized.truncate(normalized.as_str().rfind('/').unwrap_or(0)),
+      name     => {
+        normalized.push_str("/").and_then(|_| normalized.push_str(name)).map_err(|_| "Path too long")?;
+      },
+    }
+  }
+
+  if normalized.as_str().is_empty() { normalized.push_str("/")?; }
+
+  Ok(normalized)
+}
+
+/// The part of `path` below `mount_path`; `None` if it isn't below it
+fn strip_mount_path<'a>(path: &'a str, mount_path: &str) -> Option<&'a str> {
+  if mount_path == "/" { return Some(path); }
+
+  let rest = path.strip_prefix(mount_path)?;
+
+  (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+}
+
+/// The last component of `path` if it is directly inside `dir`
+fn child_name<'a>(path: &'a str, dir: &str) -> Option<&'a str> {
+  let (parent, name) = path.rsplit_once('/')?;
+  let parent = if parent.is_empty() { "/" } else { parent };
+
+  (parent == dir && !name.is_empty()).then_some(name)
+}
+
+/// The mount `path` belongs to and the rest of the path
+fn find_mount(path: &str) -> Option<(Mount, usize)> {
+  VFS.lock(|v| {
+    v.
+      mounts.
+      iter().
+      flatten().
+      filter_map(|m| strip_mount_path(path, m.path.as_str()).map(|rest| (*m, path.len() - rest.len()))).
+      max_by_key(|(m, _)| m.path.as_str().len())
+  })
+}
+
+/// Walk the normalised `path` down from the root of its mount
+fn resolve(path: &str) -> Result<(InodeRef, Option<FileSystemRef>), &'static str> {
+  let (mut inode, fs, rest) = match find_mount(path) {
+    Some((mount, prefix_len)) => (mount.fs.root()?, Some(mount.fs), &path[prefix_len..]),
+    None                      => (&empty_dir::EMPTY_DIR as InodeRef, None, path),
+  };
+
+  for name in rest.split('/').filter(|c| !c.is_empty()) {
+    let next = inode.lookup(name);
+
+    inode.release();
+    inode = next?;
+  }
+
+  Ok((inode, fs))
+}
+
+/// Register the filesystem shell commands
+pub fn init() -> Result<(), &'static str> {
+  shell::register_commands(&commands::COMMANDS)
+}
+
+/// Mount `fs` at `path`
+pub fn mount(path: &str, fs: FileSystemRef) -> Result<(), &'static str> {
+  let path = normalize(path)?;
+
+  VFS.lock(|v| {
+    if v.mounts.iter().flatten().any(|m| m.path.as_str() == path.as_str()) { return Err("Already mounted there"); }
+
+    let slot = v.mounts.iter_mut().find(|m| m.is_none()).ok_or("No free mount slots")?;
+
+    *slot = Some(Mount { path, fs });
+
+    Ok(())
+  })
+}
+
+/// Unmount the filesystem at `path`; fails while files on it are open
+pub fn unmount(path: &str) -> Result<(), &'static str> {
+  let path = normalize(path)?;
+
+  let fs = VFS.lock(|v| {
+    let slot = v.
+      mounts.
+      iter_mut().
+      find(|m| m.is_some_and(|m| m.path.as_str() == path.as_str())).
+      ok_or("Nothing mounted there")?;
+
+    let fs = slot.map(|m| m.fs).ok_or("Nothing mounted there")?;
+
+    let busy = v.
+      files.
+      iter().
+      flatten().
+      any(|f| f.fs.is_some_and(|f| core::ptr::addr_eq(f, fs)));
+
+    if busy { return Err("Filesystem busy"); }
+
+    *slot = None;
+
+    Ok(fs)
+  })?;
+
+  // Put it back if it refuses
+  fs.unmount().or_else(|e| mount(path.as_str(), fs).and(Err(e)))
+}
+
+/// Mount the FAT32 volume on the block device `device` at `path`
+pub fn mount_fat32(device: &str, path: &str) -> Result<(), &'static str> {
+  let fs = fat32::mount(block::block_manager().find(device).ok_or("No such block device")?)?;
+
+  mount(path, fs).inspect_err(|_| { let _ = fs.unmount(); })
+}
+
+/// Mount the initramfs embedded in the kernel image at `path`
+pub fn mount_initramfs(path: &str) -> Result<(), &'static str> {
+  let fs = initramfs::mount()?;
+
+  mount(path, fs).inspect_err(|_| { let _ = fs.unmount(); })
+}
+
+/// Mount the generated files describing the kernel's state at `path`
+pub fn mount_procfs(path: &str) -> Result<(), &'static str> {
+  mount(path, &procfs::PROCFS)
+}
+
+/// Call `f` with the path and the filesystem of every mount
+pub fn for_each_mount(mut f: impl FnMut(&str, FileSystemRef)) {
+  VFS.lock(|v| v.mounts.iter().flatten().for_each(|m| f(m.path.as_str(), m.fs)));
+}
+
+/// Open the file or directory at `path`
+pub fn open(path: &str) -> Result<Fd, &'static str> {
+  let path = normalize(path)?;
+  let (inode, fs) = resolve(path.as_str())?;
+
+  let fd = VFS.lock(|v| {
+    let (fd, slot) = v.files.iter_mut().enumerate().find(|(_, f)| f.is_none()).ok_or("Too many open files")?;
+
+    *slot = Some(OpenFile { path, inode, fs, offset: 0 });
+
+    Ok(Fd(fd))
+  });
+
+  if fd.is_err() { inode.release(); }
+
+  fd
+}
+
+/// The open file `fd` refers to
+fn file(fd: Fd) -> Result<OpenFile, &'static str> {
+  VFS.lock(|v| v.files.get(fd.0).copied().flatten().ok_or("Bad file descriptor"))
+}
+
+fn set_offset(fd: Fd, offset: u64) {
+  VFS.lock(|v| if let Some(Some(f)) = v.files.get_mut(fd.0) { f.offset = offset; });
+}
+
+/// Close `fd`
+pub fn close(fd: Fd) -> Result<(), &'static str> {
+  let file = VFS.lock(|v| v.files.get_mut(fd.0).and_then(|f| f.take()).ok_or("Bad file descriptor"))?;
+
+  file.inode.release();
+
+  Ok(())
+}
+
+/// Read from `fd` into `buf`; returns the number of bytes read, 0 at the end of the file
+pub fn read(fd: Fd, buf: &mut [u8]) -> Result<usize, &'static str> {
+  let file = file(fd)?;
+
+  if file.inode.stat().is_dir() { return Err("Is a directory"); }
+
+  let len = file.inode.read_at(file.offset, buf)?;
+
+  set_offset(fd, file.offset + len as u64);
+
+  Ok(len)
+}
+
+/// Write `buf` to `fd`; returns the number of bytes written
+#[allow(dead_code)]
+pub fn write(fd: Fd, buf: &[u8]) -> Result<usize, &'static str> {
+  let file = file(fd)?;
+
+  if file.inode.stat().is_dir() { return Err("Is a directory"); }
+
+  let len = file.inode.write_at(file.offset, buf)?;
+
+  set_offset(fd, file.offset + len as u64);
+
+  Ok(len)
+}
+
+/// Move the offset of `fd`; returns the new offset
+#[allow(dead_code)]
+pub fn seek(fd: Fd, pos: SeekFrom) -> Result<u64, &'static str> {
+  let file = file(fd)?;
+
+  let offset = match pos {
+    SeekFrom::Start(offset)   => Some(offset),
+    SeekFrom::Current(offset) => file.offset.checked_add_signed(offset),
+    SeekFrom::End(offset)     => file.inode.stat().size.checked_add_signed(offset),
+  };
+
+  let offset = offset.ok_or("Invalid seek")?;
+
+  set_offset(fd, offset);
+
+  Ok(offset)
+}
+
+/// Call `f` for every entry of the directory `fd`; stop early once it returns `false`
+///
+/// Mount points directly inside the directory are listed too; they hide entries of the same name
+pub fn read_dir(fd: Fd, mut f: impl FnMut(&DirEntry) -> bool) -> Result<(), &'static str> {
+  let file = file(fd)?;
+
+  if !file.inode.stat().is_dir() { return Err("Not a directory"); }
+
+  let dir = file.path.as_str();
+  let mut mount_points = [None; NUM_MOUNTS];
+
+  VFS.lock(|v| {
+    for (slot, m) in mount_points.iter_mut().zip(v.mounts.iter().flatten()) {
+      *slot = child_name(m.path.as_str(), dir).map(Name::try_from_str);
+    }
+  });
+
+  let is_mount_point = |name: &str| mount_points.iter().flatten().flatten().any(|m| m.as_str() == name);
+  let mut done = false;
+
+  file.inode.read_dir(&mut |e| {
+    if is_mount_point(e.name.as_str()) { return true; }
+
+    done = !f(e);
+
+    !done
+  })?;
+
+  if done { return Ok(()); }
+
+  let stat = Stat { file_type: FileType::Directory, size: 0 };
+
+  for name in mount_points.iter().flatten().flatten() {
+    if !f(&DirEntry { name: *name, stat }) { break; }
+  }
+
+  Ok(())
+}
+
+/// The type and size of `fd`
+#[allow(dead_code)]
+pub fn fstat(fd: Fd) -> Result<Stat, &'static str> {
+  Ok(file(fd)?.inode.stat())
+}
+
+/// The type and size of the file or directory at `path`
+pub fn stat(path: &str) -> Result<Stat, &'static str> {
+  let (inode, _) = resolve(normalize(path)?.as_str())?;
+  let stat = inode.stat();
+
+  inode.release();
+
+  Ok(stat)
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Filesystem shell commands
+
+use core::str::SplitWhitespace;
+
+use crate::{
+  fs,
+  print,
+  println,
+  shell::Command,
+};
+
+pub const COMMANDS: [Command; 5] = [
+  Command::new("mount",    "mount [<dev> <path>]: Mount a FAT32 volume",        mount),
+  Command::new("umount",   "umount <path>: Unmount a filesystem",               umount),
+  Command::new("ls",       "ls [path]: List a directory",                       ls),
+  Command::new("cat",      "cat <path>: Print a file",                          cat),
+  Command::new("stat",     "stat <path>: Print a file's type and size",         stat),
+];
+
+fn mount(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  match (args.next(), args.next()) {
+    (Some(device), Some(path)) => fs::mount_fat32(device, path),
+    (None, _)                  => {
+      fs::for_each_mount(|path, fs| println!("{} on {}", fs.name(), path));
+
+      Ok(())
+    },
+    (Some(_), None)            => Err("Missing argument"),
+  }
+}
+
+fn umount(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  fs::unmount(args.next().ok_or("Missing argument")?)
+}
+
+fn ls(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let fd = fs::open(args.next().unwrap_or("/"))?;
+  let result = fs::read_dir(fd, |e| {
+    if e.stat.is_dir() {
+      println!("{:>10}  {}/", "", e.name);
+    } else {
+      println!("{:>10}  {}", e.stat.size, e.name);
+    }
+
+    true
+  });
+
+  fs::close(fd)?;
+  result
+}
+
+fn cat(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let fd = fs::open(args.next().ok_or("Missing argument")?)?;
+  let mut buf = [0; 256];
+
+  let result = loop {
+    match fs::read(fd, &mut buf) {
+      Ok(0)   => break Ok(()),
+      Ok(len) => {
+        // Not every file is text; show what isn't as replacement characters
+        for chunk in buf[..len].utf8_chunks() {
+          print!("{}", chunk.valid());
+          if !chunk.invalid().is_empty() { print!("\u{fffd}"); }
+        }
+      },
+      Err(e)  => break Err(e),
+    }
+  };
+
+  fs::close(fd)?;
+  result
+}
+
+fn stat(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let stat = fs::stat(args.next().ok_or("Missing argument")?)?;
+
+  if stat.is_dir() {
+    println!("directory");
+  } else {
+    println!("file, {} bytes", stat.size);
+  }
+
+  Ok(())
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! The root directory while nothing is mounted at `/`
+
+use super::{
+  interface,
+  DirEntry,
+  FileType,
+  InodeRef,
+  Stat,
+};
+
+/// An empty directory
+pub struct EmptyDir;
+
+pub static EMPTY_DIR: EmptyDir = EmptyDir;
+
+impl interface::Inode for EmptyDir {
+  fn stat(&self) -> Stat {
+    Stat { file_type: FileType::Directory, size: 0 }
+  }
+
+  fn lookup(&'static self, _name: &str) -> Result<InodeRef, &'static str> {
+    Err("No such file or directory")
+  }
+
+  fn read_at(&self, _offset: u64, _buf: &mut [u8]) -> Result<usize, &'static str> {
+    Err("Is a directory")
+  }
+
+  fn read_dir(&self, _f: &mut dyn FnMut(&DirEntry) -> bool) -> Result<(), &'static str> {
+    Ok(())
+  }
+}
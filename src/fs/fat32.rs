@@ -0,0 +1,642 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Read-only FAT32
+//!
+//! The volume starts with the BIOS parameter block (BPB); after the reserved sectors come the FATs, then the data clusters
+//! Every file and directory is a chain of clusters; the FAT holds the number of the next cluster for each one
+//! Directories are arrays of 32-byte entries; a long file name is stored in extra entries just before its short (8.3) entry
+//!
+//! Names are matched case-insensitively, as FAT does; only ASCII letters are folded
+//!
+//! Mounted volumes and their inodes come from static pools; an inode slot is free again once all references to it are released
+
+use super::{
+  interface,
+  FileSystemRef,
+  FileType,
+  InodeRef,
+  Stat,
+};
+use crate::{
+  block::{
+    interface::BlockDevice,
+    BLOCK_SIZE,
+  },
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+};
+
+/// The longest name in UTF-8 bytes; longer long names fall back to the short name
+pub const MAX_NAME_LEN: usize = 255;
+
+const BOOT_SIGNATURE_OFFSET: usize = 510;
+const BOOT_SIGNATURE:        u16   = 0xaa55;
+
+const DIR_ENTRY_SIZE:    usize = 32;
+const DIR_ENTRY_END:     u8    = 0x00;
+const DIR_ENTRY_DELETED: u8    = 0xe5;
+
+/// A short name starting with 0xe5 stores it as 0x05
+const DIR_ENTRY_KANJI_E5: u8 = 0x05;
+
+const ATTR_VOLUME_ID: u8 = 0x08;
+const ATTR_DIRECTORY: u8 = 0x10;
+const ATTR_LONG_NAME: u8 = 0x0f;
+
+/// Set in the sequence number of the long name entry holding the end of the name
+const LFN_LAST:     u8    = 0x40;
+const LFN_SEQ_MASK: u8    = 0x1f;
+const LFN_CHARS:    usize = 13;
+
+/// The byte offsets of the 13 UTF-16 characters in a long name entry
+const LFN_CHAR_OFFSETS: [usize; LFN_CHARS] = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+
+/// Case flags of the short name; set by Windows NT for all-lowercase names
+const CASE_LOWER_BASE: u8 = 0x08;
+const CASE_LOWER_EXT:  u8 = 0x10;
+
+const FAT_ENTRY_MASK:   u32 = 0x0fff_ffff;
+const FAT_BAD_CLUSTER:  u32 = 0x0fff_fff7;
+const FAT_END_OF_CHAIN: u32 = 0x0fff_fff8;
+const FIRST_CLUSTER:    u32 = 2;
+
+/// The number of volumes that can be mounted at once
+const NUM_VOLUMES: usize = 4;
+
+/// The number of inodes in use per volume; directories being walked count too
+const NUM_INODES: usize = 16;
+
+/// A mounted FAT32 volume
+#[derive(Copy, Clone)]
+pub struct Fat32 {
+  device: &'static (dyn BlockDevice + Sync),
+  sectors_per_cluster: u32,
+  fat_start: u64,
+  data_start: u64,
+  root_cluster: u32,
+
+  /// The number of the last data cluster plus one
+  end_cluster: u32,
+}
+
+/// A file or directory
+#[derive(Copy, Clone)]
+pub struct DirEntry {
+  name: [u8; MAX_NAME_LEN],
+  name_len: usize,
+  attributes: u8,
+  first_cluster: u32,
+  size: u32,
+}
+
+/// An open file; reads continue where the last one stopped
+#[derive(Copy, Clone)]
+pub struct File {
+  first_cluster: u32,
+  size: u32,
+  pos: u32,
+
+  /// The cluster `pos` lies in and its index in the chain; so sequential reads don't walk the chain from the start
+  cluster: u32,
+  cluster_index: u32,
+}
+
+struct InodeInner {
+  volume: &'static Volume,
+  fat: Fat32,
+  entry: DirEntry,
+
+  /// Keeps the position in the cluster chain between reads
+  file: Option<File>,
+  refs: usize,
+}
+
+/// A file or directory on a mounted volume
+pub struct Inode {
+  inner: NullLock<Option<InodeInner>>,
+}
+
+unsafe impl Sync for Inode {}
+
+/// A mounted volume
+pub struct Volume {
+  fat: NullLock<Option<Fat32>>,
+  inodes: [Inode; NUM_INODES],
+}
+
+unsafe impl Sync for Volume {}
+
+static VOLUMES: [Volume; NUM_VOLUMES] = [const { Volume::new() }; NUM_VOLUMES];
+
+/// A long name being collected from its entries; they come last part first
+struct LongName {
+  chars: [u16; 20 * LFN_CHARS],
+  len: usize,
+  checksum: u8,
+
+  /// The sequence number the next entry must have; 0 once the name is complete
+  expected_seq: u8,
+}
+
+fn u16_at(buf: &[u8], offset: usize) -> u16 {
+  u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+  u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+/// The checksum of the short name that long name entries carry
+fn short_name_checksum(short_name: &[u8]) -> u8 {
+  short_name.iter().fold(0u8, |sum, &b| sum.rotate_right(1).wrapping_add(b))
+}
+
+impl LongName {
+  const fn new() -> Self {
+    Self {
+      chars: [0; 20 * LFN_CHARS],
+      len: 0,
+      checksum: 0,
+      expected_seq: 0,
+    }
+  }
+
+  fn reset(&mut self) {
+    self.len = 0;
+    self.expected_seq = 0;
+  }
+
+  /// Add a long name entry; out of sequence entries drop the name collected so far
+  fn add(&mut self, entry: &[u8]) {
+    let seq = entry[0] & LFN_SEQ_MASK;
+
+    if entry[0] & LFN_LAST != 0 {
+      if seq == 0 || seq as usize > self.chars.len() / LFN_CHARS { return self.reset(); }
+
+      self.len = seq as usize * LFN_CHARS;
+      self.checksum = entry[13];
+    } else if seq == 0 || self.expected_seq == 0 || seq != self.expected_seq || entry[13] != self.checksum {
+      // A continuation only belongs to a name that is being collected
+      return self.reset();
+    }
+
+    let start = (seq as usize - 1) * LFN_CHARS;
+
+    for (i, &offset) in LFN_CHAR_OFFSETS.iter().enumerate() {
+      let c = u16_at(entry, offset);
+
+      // The name ends with a 0 unless it fills its last entry; the rest is padded with 0xffff
+      if c == 0 && start + i < self.len { self.len = start + i; }
+
+      self.chars[start + i] = c;
+    }
+
+    self.expected_seq = seq - 1;
+  }
+
+  /// The name for the short entry `short_name`; if it was collected completely and belongs to it
+  fn take(&mut self, short_name: &[u8]) -> Option<&[u16]> {
+    let complete = self.len > 0 && self.expected_seq == 0 && self.checksum == short_name_checksum(short_name);
+    let len = self.len;
+
+    self.reset();
+
+    if complete { Some(&self.chars[..len]) } else { None }
+  }
+}
+
+impl DirEntry {
+  /// An entry for the root directory, which has none of its own
+  const fn root(cluster: u32) -> Self {
+    Self {
+      name: [0; MAX_NAME_LEN],
+      name_len: 0,
+      attributes: ATTR_DIRECTORY,
+      first_cluster: cluster,
+      size: 0,
+    }
+  }
+
+  /// Parse a short entry; `long_name` is used instead of the short name if it fits
+  fn parse(entry: &[u8], long_name: Option<&[u16]>) -> Self {
+    let mut e = Self {
+      name: [0; MAX_NAME_LEN],
+      name_len: 0,
+      attributes: entry[11],
+      first_cluster: ((u16_at(entry, 20) as u32) << 16) | u16_at(entry, 26) as u32,
+      size: u32_at(entry, 28),
+    };
+
+    if let Some(long_name) = long_name && e.set_long_name(long_name).is_ok() { return e; }
+
+    e.set_short_name(entry);
+    e
+  }
+
+  fn push(&mut self, c: char) -> Result<(), ()> {
+    let dst = self.name.get_mut(self.name_len..self.name_len + c.len_utf8()).ok_or(())?;
+
+    c.encode_utf8(dst);
+    self.name_len += c.len_utf8();
+
+    Ok(())
+  }
+
+  fn set_long_name(&mut self, long_name: &[u16]) -> Result<(), ()> {
+    for c in char::decode_utf16(long_name.iter().copied()) {
+      self.push(c.map_err(|_| ())?)?;
+    }
+
+    Ok(())
+  }
+
+  /// Render `NAME    EXT` as `NAME.EXT`; honouring the lowercase flags
+  fn set_short_name(&mut self, entry: &[u8]) {
+    self.name_len = 0;
+
+    let case = entry[12];
+    let lower = |b: u8, flag: u8| if case & flag != 0 { b.to_ascii_lowercase() } else { b };
+
+    for (i, &b) in entry[..8].iter().enumerate() {
+      if b == b' ' { break; }
+
+      let b = if i == 0 && b == DIR_ENTRY_KANJI_E5 { DIR_ENTRY_DELETED } else { b };
+
+      // Short names are 8-bit code page characters; only ASCII is rendered as itself
+      let _ = self.push(if b.is_ascii() { lower(b, CASE_LOWER_BASE) as char } else { '?' });
+    }
+
+    if entry[8] != b' ' {
+      let _ = self.push('.');
+
+      for &b in entry[8..11].iter().take_while(|&&b| b != b' ') {
+        let _ = self.push(if b.is_ascii() { lower(b, CASE_LOWER_EXT) as char } else { '?' });
+      }
+    }
+  }
+
+  /// The name; the long one if there is one
+  pub fn name(&self) -> &str {
+    // Only whole `char`s are ever pushed
+    core::str::from_utf8(&self.name[..self.name_len]).unwrap_or("")
+  }
+
+  /// Whether this is a directory
+  pub fn is_dir(&self) -> bool {
+    self.attributes & ATTR_DIRECTORY != 0
+  }
+
+  /// The size in bytes; 0 for directories
+  #[allow(dead_code)]
+  pub fn size(&self) -> u32 {
+    self.size
+  }
+}
+
+impl File {
+  /// The size in bytes
+  #[allow(dead_code)]
+  pub fn size(&self) -> u32 {
+    self.size
+  }
+
+  /// Where the next read starts
+  #[allow(dead_code)]
+  pub fn position(&self) -> u32 {
+    self.pos
+  }
+
+  /// Move to byte `pos`; past the end is allowed, reads there just return nothing
+  #[allow(dead_code)]
+  pub fn seek(&mut self, pos: u32) {
+    self.pos = pos;
+  }
+}
+
+impl Fat32 {
+  /// Mount the volume on `device`
+  pub fn new(device: &'static (dyn BlockDevice + Sync)) -> Result<Self, &'static str> {
+    let mut bpb = [0; BLOCK_SIZE];
+
+    device.read_blocks(0, &mut bpb)?;
+
+    if u16_at(&bpb, BOOT_SIGNATURE_OFFSET) != BOOT_SIGNATURE { return Err("No FAT boot sector"); }
+
+    let bytes_per_sector = u16_at(&bpb, 11) as usize;
+    let sectors_per_cluster = bpb[13] as u32;
+    let reserved_sectors = u16_at(&bpb, 14) as u64;
+    let num_fats = bpb[16] as u64;
+    let root_entries = u16_at(&bpb, 17);
+    let fat_size_16 = u16_at(&bpb, 22);
+    let total_sectors = u32_at(&bpb, 32) as u64;
+    let fat_size = u32_at(&bpb, 36) as u64;
+    let root_cluster = u32_at(&bpb, 44);
+
+    if bytes_per_sector != BLOCK_SIZE { return Err("Unsupported FAT sector size"); }
+    if !sectors_per_cluster.is_power_of_two() { return Err("Invalid FAT cluster size"); }
+
+    // FAT12 and FAT16 have a fixed root directory and 16-bit FAT sizes
+    if root_entries != 0 || fat_size_16 != 0 || fat_size == 0 { return Err("Not a FAT32 volume"); }
+
+    let data_start = reserved_sectors + num_fats * fat_size;
+
+    if num_fats == 0 || total_sectors <= data_start || total_sectors > device.num_blocks() {
+      return Err("Invalid FAT volume layout");
+    }
+
+    // The FAT may have room for more entries than there are clusters; never follow those
+    let num_clusters = ((total_sectors - data_start) / sectors_per_cluster as u64).min(fat_size * BLOCK_SIZE as u64 / 4 - 2);
+
+    let fat = Self {
+      device,
+      sectors_per_cluster,
+      fat_start: reserved_sectors,
+      data_start,
+      root_cluster,
+      end_cluster: num_clusters as u32 + FIRST_CLUSTER,
+    };
+
+    fat.check_cluster(root_cluster)?;
+
+    Ok(fat)
+  }
+
+  fn cluster_bytes(&self) -> u32 {
+    self.sectors_per_cluster * BLOCK_SIZE as u32
+  }
+
+  fn check_cluster(&self, cluster: u32) -> Result<(), &'static str> {
+    if !(FIRST_CLUSTER..self.end_cluster).contains(&cluster) { return Err("Corrupt FAT cluster chain"); }
+
+    Ok(())
+  }
+
+  fn cluster_lba(&self, cluster: u32) -> u64 {
+    self.data_start + (cluster - FIRST_CLUSTER) as u64 * self.sectors_per_cluster as u64
+  }
+
+  /// The cluster after `cluster`; `None` at the end of the chain
+  fn next_cluster(&self, cluster: u32) -> Result<Option<u32>, &'static str> {
+    let offset = cluster as u64 * 4;
+    let mut sector = [0; BLOCK_SIZE];
+
+    self.device.read_blocks(self.fat_start + offset / BLOCK_SIZE as u64, &mut sector)?;
+
+    let next = u32_at(&sector, (offset % BLOCK_SIZE as u64) as usize) & FAT_ENTRY_MASK;
+
+    if next >= FAT_END_OF_CHAIN { return Ok(None); }
+    if next == FAT_BAD_CLUSTER { return Err("Bad cluster in FAT chain"); }
+
+    self.check_cluster(next)?;
+
+    Ok(Some(next))
+  }
+
+  /// The root directory
+  pub fn root(&self) -> DirEntry {
+    DirEntry::root(self.root_cluster)
+  }
+
+  /// Call `f` for every entry of `dir` but `.` and `..`; stop early once it returns `false`
+  pub fn read_dir(&self, dir: &DirEntry, mut f: impl FnMut(&DirEntry) -> bool) -> Result<(), &'static str> {
+    if !dir.is_dir() { return Err("Not a directory"); }
+
+    let mut long_name = LongName::new();
+    let mut sector = [0; BLOCK_SIZE];
+    let mut cluster = Some(dir.first_cluster);
+
+    // A cycle in the chain would otherwise loop forever
+    for _ in FIRST_CLUSTER..self.end_cluster {
+      let Some(c) = cluster else { return Ok(()); };
+
+      self.check_cluster(c)?;
+
+      for s in 0..self.sectors_per_cluster as u64 {
+        self.device.read_blocks(self.cluster_lba(c) + s, &mut sector)?;
+
+        for entry in sector.chunks_exact(DIR_ENTRY_SIZE) {
+          match (entry[0], entry[11]) {
+            (DIR_ENTRY_END, _)                                  => return Ok(()),
+            (DIR_ENTRY_DELETED, _)                              => long_name.reset(),
+            (_, ATTR_LONG_NAME)                                 => long_name.add(entry),
+            (_, attributes) if attributes & ATTR_VOLUME_ID != 0 => long_name.reset(),
+            (b'.', _)                                           => long_name.reset(),
+            _                                                   => {
+              let name = long_name.take(&entry[..11]);
+
+              if !f(&DirEntry::parse(entry, name)) { return Ok(()); }
+            },
+          }
+        }
+      }
+
+      cluster = self.next_cluster(c)?;
+    }
+
+    Err("Corrupt FAT cluster chain")
+  }
+
+  /// The entry named `name` in `dir`
+  pub fn find(&self, dir: &DirEntry, name: &str) -> Result<DirEntry, &'static str> {
+    let mut found = None;
+
+    self.read_dir(dir, |e| {
+      if e.name().eq_ignore_ascii_case(name) { found = Some(*e); }
+
+      found.is_none()
+    })?;
+
+    found.ok_or("No such file or directory")
+  }
+
+  /// The entry at `path`; relative to the root directory
+  #[allow(dead_code)]
+  pub fn lookup(&self, path: &str) -> Result<DirEntry, &'static str> {
+    path.
+      split('/').
+      filter(|c| !c.is_empty() && *c != ".").
+      try_fold(self.root(), |dir, name| self.find(&dir, name))
+  }
+
+  /// Open the file `entry`
+  pub fn open(&self, entry: &DirEntry) -> Result<File, &'static str> {
+    if entry.is_dir() { return Err("Is a directory"); }
+
+    Ok(File {
+      first_cluster: entry.first_cluster,
+      size: entry.size,
+      pos: 0,
+      cluster: entry.first_cluster,
+      cluster_index: 0,
+    })
+  }
+
+  /// Read from `file` into `buf`; returns the number of bytes read, 0 at the end of the file
+  pub fn read(&self, file: &mut File, buf: &mut [u8]) -> Result<usize, &'static str> {
+    let mut done = 0;
+    let mut sector = [0; BLOCK_SIZE];
+
+    while done < buf.len() && file.pos < file.size {
+      let index = file.pos / self.cluster_bytes();
+
+      // A seek backwards starts over from the first cluster
+      if index < file.cluster_index {
+        file.cluster = file.first_cluster;
+        file.cluster_index = 0;
+      }
+
+      while file.cluster_index < index {
+        file.cluster = self.next_cluster(file.cluster)?.ok_or("FAT cluster chain shorter than the file")?;
+        file.cluster_index += 1;
+      }
+
+      self.check_cluster(file.cluster)?;
+
+      let offset = file.pos % self.cluster_bytes();
+      let lba = self.cluster_lba(file.cluster) + (offset as usize / BLOCK_SIZE) as u64;
+      let sector_offset = offset as usize % BLOCK_SIZE;
+      let len = (BLOCK_SIZE - sector_offset).min(buf.len() - done).min((file.size - file.pos) as usize);
+
+      self.device.read_blocks(lba, &mut sector)?;
+      buf[done..done + len].copy_from_slice(&sector[sector_offset..sector_offset + len]);
+
+      done += len;
+      file.pos += len as u32;
+    }
+
+    Ok(done)
+  }
+}
+
+impl DirEntry {
+  fn stat(&self) -> Stat {
+    if self.is_dir() {
+      Stat { file_type: FileType::Directory, size: 0 }
+    } else {
+      Stat { file_type: FileType::File, size: self.size as u64 }
+    }
+  }
+}
+
+impl Inode {
+  const fn new() -> Self {
+    Self {
+      inner: NullLock::new(None),
+    }
+  }
+
+  fn with<R>(&self, f: impl FnOnce(&mut InodeInner) -> R) -> R {
+    self.inner.lock(|i| f(i.as_mut().expect("Released FAT32 inode used")))
+  }
+}
+
+impl interface::Inode for Inode {
+  fn stat(&self) -> Stat {
+    self.with(|i| i.entry.stat())
+  }
+
+  fn lookup(&'static self, name: &str) -> Result<InodeRef, &'static str> {
+    let (volume, fat, dir) = self.with(|i| (i.volume, i.fat, i.entry));
+
+    volume.acquire(fat, fat.find(&dir, name)?)
+  }
+
+  fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, &'static str> {
+    self.with(|i| {
+      let file = i.file.as_mut().ok_or("Is a directory")?;
+
+      // FAT32 files are smaller than 4 GiB
+      let Ok(pos) = u32::try_from(offset) else { return Ok(0); };
+
+      file.seek(pos);
+      i.fat.read(file, buf)
+    })
+  }
+
+  fn read_dir(&self, f: &mut dyn FnMut(&super::DirEntry) -> bool) -> Result<(), &'static str> {
+    let (fat, dir) = self.with(|i| (i.fat, i.entry));
+
+    fat.read_dir(&dir, |e| match super::DirEntry::new(e.name(), e.stat()) {
+      Ok(entry) => f(&entry),
+      Err(_)    => true,
+    })
+  }
+
+  fn release(&self) {
+    self.inner.lock(|i| {
+      if let Some(inner) = i {
+        inner.refs -= 1;
+
+        if inner.refs == 0 { *i = None; }
+      }
+    });
+  }
+}
+
+impl Volume {
+  const fn new() -> Self {
+    Self {
+      fat: NullLock::new(None),
+      inodes: [const { Inode::new() }; NUM_INODES],
+    }
+  }
+
+  /// A free inode slot for `entry`
+  fn acquire(&'static self, fat: Fat32, entry: DirEntry) -> Result<InodeRef, &'static str> {
+    let file = if entry.is_dir() { None } else { Some(fat.open(&entry)?) };
+
+    self.
+      inodes.
+      iter().
+      find(|inode| inode.inner.lock(|i| {
+        if i.is_some() { return false; }
+
+        *i = Some(InodeInner { volume: self, fat, entry, file, refs: 1 });
+
+        true
+      })).
+      map(|inode| inode as InodeRef).
+      ok_or("Too many FAT32 inodes in use")
+  }
+}
+
+impl interface::FileSystem for Volume {
+  fn name(&self) -> &'static str {
+    "fat32"
+  }
+
+  fn root(&'static self) -> Result<InodeRef, &'static str> {
+    let fat = self.fat.lock(|f| *f).ok_or("FAT32 volume not mounted")?;
+
+    self.acquire(fat, fat.root())
+  }
+
+  fn unmount(&self) -> Result<(), &'static str> {
+    if self.inodes.iter().any(|inode| inode.inner.lock(|i| i.is_some())) { return Err("FAT32 inodes still in use"); }
+
+    self.fat.lock(|f| *f = None);
+
+    Ok(())
+  }
+}
+
+/// Mount the FAT32 volume on `device`
+pub fn mount(device: &'static (dyn BlockDevice + Sync)) -> Result<FileSystemRef, &'static str> {
+  let fat = Fat32::new(device)?;
+
+  VOLUMES.
+    iter().
+    find(|v| v.fat.lock(|f| {
+      if f.is_some() { return false; }
+
+      *f = Some(fat);
+
+      true
+    })).
+    map(|v| v as FileSystemRef).
+    ok_or("Too many FAT32 volumes mounted")
+}
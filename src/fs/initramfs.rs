@@ -0,0 +1,276 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Read-only initramfs
+//!
+//! The build script packs the `initramfs` directory into a ustar archive which is embedded in the kernel image
+//! Every member starts with a 512-byte header holding its name, octal size and type; its contents follow, padded to 512 bytes
+//! The archive ends with a zero block
+//!
+//! Mounting indexes the archive into a static pool of nodes; file contents are read straight from the image
+//! Directories that only show up as part of a path get a node of their own
+
+use super::{
+  interface,
+  FileSystemRef,
+  FileType,
+  InodeRef,
+  Path,
+  Stat,
+};
+use crate::synchronization::{
+  interface::Mutex,
+  NullLock,
+};
+
+const NUM_NODES: usize = 64;
+
+const BLOCK_SIZE: usize = 512;
+
+const NAME:      core::ops::Range<usize> = 0..100;
+const SIZE:      core::ops::Range<usize> = 124..136;
+const CHECKSUM:  core::ops::Range<usize> = 148..156;
+const TYPE_FLAG: usize                   = 156;
+const MAGIC:     core::ops::Range<usize> = 257..262;
+const PREFIX:    core::ops::Range<usize> = 345..500;
+
+const TYPE_FILE:     u8 = b'0';
+const TYPE_FILE_OLD: u8 = b'\0';
+const TYPE_DIR:      u8 = b'5';
+
+static ARCHIVE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/initramfs.tar"));
+
+#[derive(Copy, Clone)]
+struct NodeInner {
+  /// Relative to the root, without leading or trailing slashes; empty for the root
+  path: Path,
+  file_type: FileType,
+  data: &'static [u8],
+}
+
+/// A file or directory in the archive
+pub struct Node {
+  inner: NullLock<Option<NodeInner>>,
+}
+
+unsafe impl Sync for Node {}
+
+/// The mounted archive
+pub struct Initramfs {
+  mounted: NullLock<bool>,
+  nodes: [Node; NUM_NODES],
+}
+
+unsafe impl Sync for Initramfs {}
+
+static INITRAMFS: Initramfs = Initramfs {
+  mounted: NullLock::new(false),
+  nodes: [const { Node { inner: NullLock::new(None) } }; NUM_NODES],
+};
+
+/// Parse the octal number in `field`; which may be padded with spaces and NULs
+fn octal(field: &[u8]) -> Result<usize, &'static str> {
+  field.
+    iter().
+    skip_while(|&&b| b == b' ').
+    take_while(|&&b| b != b' ' && b != b'\0').
+    try_fold(0usize, |n, &b| match b {
+      b'0'..=b'7' => n.checked_mul(8).map(|n| n + usize::from(b - b'0')).ok_or("initramfs: number too large"),
+      _           => Err("initramfs: malformed number"),
+    })
+}
+
+/// The NUL-terminated string in `field`
+fn c_str(field: &[u8]) -> Result<&str, &'static str> {
+  let len = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+
+  core::str::from_utf8(&field[..len]).map_err(|_| "initramfs: name is not UTF-8")
+}
+
+/// The part of `path` up to its last slash; empty for top-level entries
+fn parent(path: &str) -> &str {
+  path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+/// `dir` and `name` joined with a slash
+fn join(dir: &str, name: &str) -> Result<Path, &'static str> {
+  let mut path = Path::try_from_str(dir)?;
+
+  if !dir.is_empty() { path.push_str("/")?; }
+  path.push_str(name)?;
+
+  Ok(path)
+}
+
+impl Node {
+  fn get(&self) -> Option<NodeInner> {
+    self.inner.lock(|i| *i)
+  }
+
+  fn with<R>(&self, f: impl FnOnce(&NodeInner) -> R) -> R {
+    self.inner.lock(|i| f(i.as_ref().expect("Unmounted initramfs node used")))
+  }
+}
+
+impl interface::Inode for Node {
+  fn stat(&self) -> Stat {
+    self.with(|i| Stat { file_type: i.file_type, size: i.data.len() as u64 })
+  }
+
+  fn lookup(&'static self, name: &str) -> Result<InodeRef, &'static str> {
+    let dir = self.with(|i| i.path);
+    let path = join(dir.as_str(), name).map_err(|_| "No such file or directory")?;
+
+    INITRAMFS.find(path.as_str()).ok_or("No such file or directory")
+  }
+
+  fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, &'static str> {
+    let inner = self.with(|i| *i);
+
+    if inner.file_type == FileType::Directory { return Err("Is a directory"); }
+
+    let Some(rest) = usize::try_from(offset).ok().and_then(|o| inner.data.get(o..)) else { return Ok(0); };
+    let len = rest.len().min(buf.len());
+
+    buf[..len].copy_from_slice(&rest[..len]);
+
+    Ok(len)
+  }
+
+  fn read_dir(&self, f: &mut dyn FnMut(&super::DirEntry) -> bool) -> Result<(), &'static str> {
+    let dir = self.with(|i| i.path);
+
+    for node in INITRAMFS.nodes.iter().filter_map(Node::get) {
+      let path = node.path.as_str();
+
+      if path.is_empty() || parent(path) != dir.as_str() { continue; }
+
+      let name = path.rsplit('/').next().unwrap_or(path);
+      let stat = Stat { file_type: node.file_type, size: node.data.len() as u64 };
+
+      if let Ok(entry) = super::DirEntry::new(name, stat) && !f(&entry) { break; }
+    }
+
+    Ok(())
+  }
+}
+
+impl Initramfs {
+  /// The node at `path`
+  fn find(&'static self, path: &str) -> Option<InodeRef> {
+    self.
+      nodes.
+      iter().
+      find(|n| n.get().is_some_and(|n| n.path.as_str() == path)).
+      map(|n| n as InodeRef)
+  }
+
+  /// Add a node; or replace the one at the same path, as a later member of an archive overrides an earlier one
+  fn insert(&self, path: &str, file_type: FileType, data: &'static [u8]) -> Result<(), &'static str> {
+    let node = NodeInner { path: Path::try_from_str(path).map_err(|_| "initramfs: path too long")?, file_type, data };
+
+    let existing = self.nodes.iter().find(|n| n.get().is_some_and(|n| n.path.as_str() == path));
+    let slot = existing.or_else(|| self.nodes.iter().find(|n| n.get().is_none())).ok_or("initramfs: too many files")?;
+
+    slot.inner.lock(|i| *i = Some(node));
+
+    Ok(())
+  }
+
+  /// Add directory nodes for all parents of `path` that don't have one yet
+  fn insert_parents(&self, path: &str) -> Result<(), &'static str> {
+    for (i, _) in path.match_indices('/') {
+      let dir = &path[..i];
+
+      if self.nodes.iter().all(|n| n.get().is_none_or(|n| n.path.as_str() != dir)) {
+        self.insert(dir, FileType::Directory, &[])?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Index the members of the archive
+  fn parse(&self, mut archive: &'static [u8]) -> Result<(), &'static str> {
+    self.insert("", FileType::Directory, &[])?;
+
+    while archive.len() >= BLOCK_SIZE {
+      let (header, rest) = archive.split_at(BLOCK_SIZE);
+
+      if header.iter().all(|&b| b == 0) { break; }
+
+      if &header[MAGIC] != b"ustar" { return Err("initramfs: not a ustar archive"); }
+
+      // The checksum is the sum of the header bytes with its own field counted as spaces
+      let sum = header.
+        iter().
+        enumerate().
+        map(|(i, &b)| if CHECKSUM.contains(&i) { u32::from(b' ') } else { u32::from(b) }).
+        sum::<u32>();
+
+      if octal(&header[CHECKSUM])? != sum as usize { return Err("initramfs: header checksum mismatch"); }
+
+      let size = octal(&header[SIZE])?;
+      let data = rest.get(..size).ok_or("initramfs: truncated archive")?;
+
+      let mut path = Path::try_from_str(c_str(&header[PREFIX])?).map_err(|_| "initramfs: path too long")?;
+
+      if !path.as_str().is_empty() { path.push_str("/")?; }
+      path.push_str(c_str(&header[NAME])?).map_err(|_| "initramfs: path too long")?;
+
+      let name = path.as_str().trim_start_matches("./").trim_matches('/');
+
+      let file_type = match header[TYPE_FLAG] {
+        TYPE_FILE | TYPE_FILE_OLD => Some(FileType::File),
+        TYPE_DIR                  => Some(FileType::Directory),
+        // Links, devices and the like are skipped
+        _                         => None,
+      };
+
+      if let Some(file_type) = file_type.filter(|_| !name.is_empty()) {
+        self.insert_parents(name)?;
+        self.insert(name, file_type, data)?;
+      }
+
+      archive = rest.get(size.next_multiple_of(BLOCK_SIZE)..).unwrap_or(&[]);
+    }
+
+    Ok(())
+  }
+
+  fn clear(&self) {
+    self.nodes.iter().for_each(|n| n.inner.lock(|i| *i = None));
+  }
+}
+
+impl interface::FileSystem for Initramfs {
+  fn name(&self) -> &'static str {
+    "initramfs"
+  }
+
+  fn root(&'static self) -> Result<InodeRef, &'static str> {
+    self.find("").ok_or("initramfs not mounted")
+  }
+
+  fn unmount(&self) -> Result<(), &'static str> {
+    self.clear();
+    self.mounted.lock(|m| *m = false);
+
+    Ok(())
+  }
+}
+
+/// Index the archive embedded in the kernel image; it can only be mounted once at a time
+pub fn mount() -> Result<FileSystemRef, &'static str> {
+  let fs = &INITRAMFS;
+
+  fs.mounted.lock(|m| if *m { Err("initramfs already mounted") } else { *m = true; Ok(()) })?;
+
+  fs.parse(ARCHIVE).inspect_err(|_| {
+    fs.clear();
+    fs.mounted.lock(|m| *m = false);
+  })?;
+
+  Ok(fs)
+}
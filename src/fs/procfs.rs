@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Kernel state as generated files
+//!
+//! The contents of a file are generated again on every read; nothing is buffered
+//! A read renders the whole file and keeps only the bytes that fall in the requested range
+
+use core::fmt;
+
+use super::{
+  interface,
+  DirEntry,
+  FileType,
+  InodeRef,
+  Stat,
+};
+use crate::{
+  bsp,
+  driver,
+  exception,
+  time,
+};
+
+/// A file whose contents are written by `generate`
+struct ProcFile {
+  name: &'static str,
+  generate: fn(&mut dyn fmt::Write) -> fmt::Result,
+}
+
+/// The one directory; holding all files
+struct ProcDir;
+
+/// The filesystem
+pub struct ProcFs;
+
+static FILES: [ProcFile; 4] = [
+  ProcFile { name: "drivers",    generate: drivers    },
+  ProcFile { name: "interrupts", generate: interrupts },
+  ProcFile { name: "memmap",     generate: memmap     },
+  ProcFile { name: "uptime",     generate: uptime     },
+];
+
+static ROOT: ProcDir = ProcDir;
+
+pub static PROCFS: ProcFs = ProcFs;
+
+/// Keeps the part of the output that falls in `buf`; which starts `offset` bytes into it
+struct Window<'a> {
+  buf: &'a mut [u8],
+  offset: usize,
+
+  /// The number of bytes written so far
+  pos: usize,
+}
+
+impl fmt::Write for Window<'_> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    let start = self.pos.max(self.offset);
+    let end = (self.pos + s.len()).min(self.offset + self.buf.len());
+
+    if start < end {
+      self.buf[start - self.offset..end - self.offset].copy_from_slice(&s.as_bytes()[start - self.pos..end - self.pos]);
+    }
+
+    self.pos += s.len();
+
+    Ok(())
+  }
+}
+
+fn drivers(w: &mut dyn fmt::Write) -> fmt::Result {
+  driver::driver_manager().write_list(w)
+}
+
+fn interrupts(w: &mut dyn fmt::Write) -> fmt::Result {
+  exception::asynchronous::write_state(w)
+}
+
+fn memmap(w: &mut dyn fmt::Write) -> fmt::Result {
+  bsp::memory::mmu::virt_mem_layout().write_layout(w)
+}
+
+fn uptime(w: &mut dyn fmt::Write) -> fmt::Result {
+  let uptime = time::time_manager().uptime();
+
+  writeln!(w, "{}.{:06}", uptime.as_secs(), uptime.subsec_micros())
+}
+
+impl ProcFile {
+  fn stat(&self) -> Stat {
+    let mut window = Window { buf: &mut [], offset: 0, pos: 0 };
+    let _ = (self.generate)(&mut window);
+
+    Stat { file_type: FileType::File, size: window.pos as u64 }
+  }
+}
+
+impl interface::Inode for ProcFile {
+  fn stat(&self) -> Stat {
+    ProcFile::stat(self)
+  }
+
+  fn lookup(&'static self, _name: &str) -> Result<InodeRef, &'static str> {
+    Err("Not a directory")
+  }
+
+  fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, &'static str> {
+    let Ok(offset) = usize::try_from(offset) else { return Ok(0); };
+    let mut window = Window { buf, offset, pos: 0 };
+
+    (self.generate)(&mut window).map_err(|_| "Error generating file")?;
+
+    Ok(window.pos.saturating_sub(offset).min(window.buf.len()))
+  }
+
+  fn read_dir(&self, _f: &mut dyn FnMut(&DirEntry) -> bool) -> Result<(), &'static str> {
+    Err("Not a directory")
+  }
+}
+
+impl interface::Inode for ProcDir {
+  fn stat(&self) -> Stat {
+    Stat { file_type: FileType::Directory, size: 0 }
+  }
+
+  fn lookup(&'static self, name: &str) -> Result<InodeRef, &'static str> {
+    FILES.
+      iter().
+      find(|f| f.name == name).
+      map(|f| f as InodeRef).
+      ok_or("No such file or directory")
+  }
+
+  fn read_at(&self, _offset: u64, _buf: &mut [u8]) -> Result<usize, &'static str> {
+    Err("Is a directory")
+  }
+
+  fn read_dir(&self, f: &mut dyn FnMut(&DirEntry) -> bool) -> Result<(), &'static str> {
+    for file in FILES.iter() {
+      if !f(&DirEntry::new(file.name, file.stat())?) { break; }
+    }
+
+    Ok(())
+  }
+}
+
+impl interface::FileSystem for ProcFs {
+  fn name(&self) -> &'static str {
+    "procfs"
+  }
+
+  fn root(&'static self) -> Result<InodeRef, &'static str> {
+    Ok(&ROOT)
+  }
+}
@@ -0,0 +1,290 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Leveled logging
+//!
+//! Records are emitted through the `error!`, `warn!`, `info!`, `debug!` and `trace!` macros
+//! A record is printed when its level is at or below the effective level for the module it came from:
+//! the level of the longest matching module filter, or the global max level if no filter matches
+//!
+//! Every record goes to all console sinks whose own level admits it
+//! One of them is the in-memory kernel log (akin to `dmesg`); registered from the start, it captures records logged before any real console exists
+//! Those early records are replayed to the first console added afterwards
+
+mod kernel_log;
+mod ring_buffer;
+
+use core::{
+  fmt,
+  str::FromStr,
+};
+
+use kernel_log::KernelLog;
+
+use crate::{
+  common::FixedString,
+  console::{
+    self,
+    ansi,
+  },
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+  time,
+};
+
+/// Log levels; from most to least severe
+#[allow(dead_code)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Level {
+  Error,
+  Warn,
+  Info,
+  Debug,
+  Trace,
+}
+
+/// The most verbose level compiled into the kernel
+/// Records above this level are stripped at compile time; `debug!` and `trace!` only exist in debug builds
+pub const STATIC_MAX_LEVEL: Level = if cfg!(debug_assertions) { Level::Trace } else { Level::Info };
+
+const NUM_MODULE_FILTERS: usize = 8;
+
+/// The longest line a `LineLogger` logs as one record
+const LINE_LENGTH: usize = 128;
+
+/// Per-module level override; matches the module and all of its submodules
+#[derive(Copy, Clone)]
+struct ModuleFilter {
+  module: &'static str,
+  level: Level,
+}
+
+struct LoggerInner {
+  max_level: Level,
+  module_filters: [Option<ModuleFilter>; NUM_MODULE_FILTERS],
+}
+
+static LOGGER: NullLock<LoggerInner> = NullLock::new(LoggerInner::new());
+
+/// Name of the kernel log's console sink
+pub const KERNEL_LOG_SINK_NAME: &str = "kernel log";
+
+/// The in-memory kernel log
+pub static KERNEL_LOG: KernelLog = KernelLog::new();
+
+impl Level {
+  /// The prefix printed in front of each record
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Level::Error => "ERROR",
+      Level::Warn  => "WARN",
+      Level::Info  => "INFO",
+      Level::Debug => "DEBUG",
+      Level::Trace => "TRACE",
+    }
+  }
+
+  /// The colour the prefix is printed in
+  const fn color(&self) -> ansi::Color {
+    match self {
+      Level::Error => ansi::Color::Red,
+      Level::Warn  => ansi::Color::Yellow,
+      Level::Info  => ansi::Color::Green,
+      Level::Debug => ansi::Color::Blue,
+      Level::Trace => ansi::Color::Magenta,
+    }
+  }
+}
+
+impl FromStr for Level {
+  type Err = &'static str;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "error" => Ok(Level::Error),
+      "warn"  => Ok(Level::Warn),
+      "info"  => Ok(Level::Info),
+      "debug" => Ok(Level::Debug),
+      "trace" => Ok(Level::Trace),
+      _       => Err("Unknown log level"),
+    }
+  }
+}
+
+impl fmt::Display for Level {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.pad(self.as_str())
+  }
+}
+
+impl ModuleFilter {
+  /// `module` matches `self.module` itself or any module nested in it
+  fn matches(&self, module: &str) -> bool {
+    match module.strip_prefix(self.module) {
+      Some(rest) => rest.is_empty() || rest.starts_with("::"),
+      None       => false,
+    }
+  }
+}
+
+impl LoggerInner {
+  /// Create an instance
+  pub const fn new() -> Self {
+    Self {
+      max_level: Level::Info,
+      module_filters: [None; NUM_MODULE_FILTERS],
+    }
+  }
+
+  /// The level of the most specific filter matching `module`; the global max level otherwise
+  fn level_for(&self, module: &str) -> Level {
+    self.
+      module_filters.
+      iter().
+      filter_map(|f| f.as_ref()).
+      filter(|f| f.matches(module)).
+      max_by_key(|f| f.module.len()).
+      map_or(self.max_level, |f| f.level)
+  }
+
+  fn set_module_level(&mut self, module: &'static str, level: Level) -> Result<(), &'static str> {
+    let existing = self.
+      module_filters.
+      iter_mut().
+      flatten().
+      find(|f| f.module == module);
+
+    if let Some(filter) = existing {
+      filter.level = level;
+
+      return Ok(());
+    }
+
+    match self.module_filters.iter_mut().find(|f| f.is_none()) {
+      Some(free) => {
+        *free = Some(ModuleFilter { module, level });
+
+        Ok(())
+      }
+      None => Err("No free module filter slots"),
+    }
+  }
+}
+
+/// Set the global max level; applies to all modules without a module filter
+pub fn set_max_level(level: Level) {
+  LOGGER.lock(|l| l.max_level = level);
+}
+
+/// The global max level
+pub fn max_level() -> Level {
+  LOGGER.lock(|l| l.max_level)
+}
+
+/// Set the level for `module` (as reported by `module_path!()`) and its submodules; e.g. `kernel::bsp`
+#[allow(dead_code)]
+pub fn set_module_level(module: &'static str, level: Level) -> Result<(), &'static str> {
+  LOGGER.lock(|l| l.set_module_level(module, level))
+}
+
+/// Remove the filter for `module`; its records fall back to the global max level
+#[allow(dead_code)]
+pub fn clear_module_level(module: &str) {
+  LOGGER.lock(|l| {
+    l.
+      module_filters.
+      iter_mut().
+      filter(|f| matches!(f, Some(f) if f.module == module)).
+      for_each(|f| *f = None)
+  });
+}
+
+/// Whether a record of `level` from `module` would be printed
+pub fn enabled(level: Level, module: &str) -> bool {
+  level <= STATIC_MAX_LEVEL && LOGGER.lock(|l| level <= l.level_for(module))
+}
+
+#[doc(hidden)]
+pub fn _log(level: Level, module: &'static str, args: fmt::Arguments) {
+  if !enabled(level, module) { return; }
+
+  let timestamp = time::time_manager().uptime();
+  let record = format_args!(
+    "[  {:>3}.{:06}] {}{:<5}{} {}\n",
+    timestamp.as_secs(),
+    timestamp.subsec_micros(),
+    ansi::Fg(level.color()),
+    level,
+    ansi::RESET,
+    args,
+  );
+
+  console::write_record(level, record).unwrap();
+}
+
+/// A writer that logs every line written to it as a record; so `write_*` functions can be printed, too
+///
+/// Longer lines are split; a final line without a newline is logged when the writer is dropped
+pub struct LineLogger {
+  level: Level,
+  module: &'static str,
+
+  /// Put in front of every line
+  prefix: &'static str,
+  line: FixedString<LINE_LENGTH>,
+}
+
+impl LineLogger {
+  /// Create an instance logging records of `level` from `module`; e.g. `module_path!()`
+  pub const fn new(level: Level, module: &'static str, prefix: &'static str) -> Self {
+    Self {
+      level,
+      module,
+      prefix,
+      line: FixedString::new(),
+    }
+  }
+
+  fn log_line(&mut self) {
+    _log(self.level, self.module, format_args!("{}{}", self.prefix, self.line));
+
+    self.line.truncate(0);
+  }
+}
+
+impl fmt::Write for LineLogger {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    for c in s.chars() {
+      if c == '\n' {
+        self.log_line();
+        continue;
+      }
+
+      let mut buf = [0; 4];
+      let c = c.encode_utf8(&mut buf);
+
+      if self.line.push_str(c).is_err() {
+        self.log_line();
+        let _ = self.line.push_str(c);
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl Drop for LineLogger {
+  fn drop(&mut self) {
+    if !self.line.as_str().is_empty() { self.log_line(); }
+  }
+}
+
+/// Write the whole kernel log to all other console sinks
+pub fn dump() {
+  console::for_each_sink(|s| {
+    if s.name != KERNEL_LOG_SINK_NAME { KERNEL_LOG.write_to(s.writer); }
+  });
+}
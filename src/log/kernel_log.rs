@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! The in-memory kernel log
+//!
+//! A console sink that keeps the most recent output in a ring buffer, so it can be replayed or dumped later on
+
+use core::fmt;
+
+use super::ring_buffer::RingBuffer;
+use crate::{
+  console,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+  },
+};
+
+/// Size of the kernel log in bytes
+const KERNEL_LOG_SIZE: usize = 16 * 1024;
+
+struct KernelLogInner {
+  buffer: RingBuffer<KERNEL_LOG_SIZE>,
+  early_records_replayed: bool,
+}
+
+pub struct KernelLog {
+  inner: IRQSafeNullLock<KernelLogInner>,
+}
+
+unsafe impl Sync for KernelLog {}
+
+impl KernelLogInner {
+  /// Create an instance
+  pub const fn new() -> Self {
+    Self {
+      buffer: RingBuffer::new(),
+      early_records_replayed: false,
+    }
+  }
+
+  /// Write the buffered output to `writer`
+  ///
+  /// If older output has been overwritten the first, partial, line is skipped
+  fn write_to(&self, writer: &dyn console::interface::Write) {
+    let (mut older, mut newer) = self.buffer.as_slices();
+
+    if self.buffer.has_wrapped() {
+      match older.iter().position(|&b| b == b'\n') {
+        Some(i) => older = &older[i + 1..],
+        None    => {
+          older = &[];
+          newer = newer.iter().position(|&b| b == b'\n').map_or(&[], |i| &newer[i + 1..]);
+        }
+      }
+    }
+
+    // A character may be split between the two halves; its bytes are put back together first
+    let tail = incomplete_tail(older);
+    let (older, tail) = older.split_at(older.len() - tail);
+    let head = newer.iter().take(4 - tail.len()).take_while(|&&b| b & 0xc0 == 0x80).count();
+    let (head, newer) = newer.split_at(head);
+
+    let mut split = [0; 4];
+    split[..tail.len()].copy_from_slice(tail);
+    split[tail.len()..tail.len() + head.len()].copy_from_slice(head);
+
+    write_utf8(writer, older);
+    write_utf8(writer, &split[..tail.len() + head.len()]);
+    write_utf8(writer, newer);
+  }
+}
+
+/// The number of bytes at the end of `bytes` that start a character without completing it
+fn incomplete_tail(bytes: &[u8]) -> usize {
+  for len in 1..=bytes.len().min(3) {
+    let b = bytes[bytes.len() - len];
+
+    // Skip continuation bytes back to the one that starts the character
+    if b & 0xc0 == 0x80 { continue; }
+
+    let needed = match b {
+      0xc0..=0xdf => 2,
+      0xe0..=0xef => 3,
+      0xf0..=0xf7 => 4,
+      _           => 1,
+    };
+
+    return if needed > len { len } else { 0 };
+  }
+
+  0
+}
+
+/// Write `bytes` as text; invalid UTF-8 becomes replacement characters
+fn write_utf8(writer: &dyn console::interface::Write, bytes: &[u8]) {
+  for chunk in bytes.utf8_chunks() {
+    let _ = writer.write_fmt(format_args!("{}", chunk.valid()));
+
+    if !chunk.invalid().is_empty() { writer.write_char(char::REPLACEMENT_CHARACTER); }
+  }
+}
+
+impl KernelLog {
+  /// Create an instance
+  ///
+  /// Zero-initialized; so a static instance lands in `.bss` and captures output from the very first instruction
+  pub const fn new() -> Self {
+    Self {
+      inner: IRQSafeNullLock::new(KernelLogInner::new()),
+    }
+  }
+
+  /// Write everything captured so far to `writer`; only the first call has an effect
+  pub fn replay_early_records(&self, writer: &dyn console::interface::Write) {
+    self.inner.lock(|i| {
+      if i.early_records_replayed { return; }
+
+      i.early_records_replayed = true;
+      i.write_to(writer);
+    });
+  }
+
+  /// Write the whole kernel log to `writer`
+  pub fn write_to(&self, writer: &dyn console::interface::Write) {
+    self.inner.lock(|i| i.write_to(writer));
+  }
+}
+
+impl console::interface::Write for KernelLog {
+  fn write_char(&self, c: char) {
+    self.inner.lock(|i| i.buffer.write_bytes(c.encode_utf8(&mut [0; 4]).as_bytes()));
+  }
+
+  fn write_fmt(&self, args: fmt::Arguments) -> fmt::Result {
+    self.inner.lock(|i| fmt::Write::write_fmt(&mut i.buffer, args))
+  }
+
+  fn flush(&self) {}
+}
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Fixed-size byte ring buffer backing the kernel log
+//!
+//! Once full, new bytes overwrite the oldest ones
+
+use core::fmt;
+
+pub struct RingBuffer<const SIZE: usize> {
+  data: [u8; SIZE],
+
+  /// Total number of bytes ever written; `written % SIZE` is the next write position
+  written: usize,
+}
+
+impl<const SIZE: usize> RingBuffer<SIZE> {
+  /// Create an instance
+  ///
+  /// All members are zero; so a static instance lands in `.bss` and is usable before any Rust code runs
+  pub const fn new() -> Self {
+    Self {
+      data: [0; SIZE],
+      written: 0,
+    }
+  }
+
+  /// Append bytes; overwriting the oldest ones if the buffer is full
+  pub fn write_bytes(&mut self, bytes: &[u8]) {
+    for &b in bytes {
+      self.data[self.written % SIZE] = b;
+      self.written += 1;
+    }
+  }
+
+  /// Whether older bytes have been overwritten
+  pub fn has_wrapped(&self) -> bool {
+    self.written > SIZE
+  }
+
+  /// The stored bytes, oldest first, as two contiguous slices
+  pub fn as_slices(&self) -> (&[u8], &[u8]) {
+    if !self.has_wrapped() { return (&self.data[..self.written], &[]); }
+
+    let (newer, older) = self.data.split_at(self.written % SIZE);
+
+    (older, newer)
+  }
+}
+
+impl<const SIZE: usize> fmt::Write for RingBuffer<SIZE> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    self.write_bytes(s.as_bytes());
+
+    Ok(())
+  }
+}
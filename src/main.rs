@@ -18,16 +18,25 @@
 #![no_main]
 #![no_std]
 
+mod block;
 mod bsp;
 mod common;
 mod console;
 mod cpu;
 mod driver;
 mod exception;
+mod executor;
+mod fs;
+mod log;
 mod memory;
 mod panic_wait;
+mod power;
 mod print;
+mod random;
+mod shell;
+mod symbols;
 mod synchronization;
+mod thread;
 mod time;
 
 ///
@@ -44,6 +53,9 @@ fn kernel_init() -> ! {
     panic!("MMU: {}", string);
   }
 
+  // The built-in shell commands first; the drivers and subsystems initialized below add their own
+  shell::init();
+
   // Initialize the BSP driver subsystem
   if let Err(e) = unsafe { bsp::driver::init() } {
     panic!("Error initializing BSP driver subsystem: {}", e);
@@ -53,17 +65,54 @@ fn kernel_init() -> ! {
   driver::driver_manager().init_drivers();
   // println! is usable from here on
 
+  if let Err(e) = power::init() {
+    panic!("Error initializing power control: {}", e);
+  }
+
+  if let Err(e) = block::init() {
+    panic!("Error initializing block devices: {}", e);
+  }
+
+  if let Err(e) = fs::init() {
+    panic!("Error initializing the filesystem layer: {}", e);
+  }
+
+  if let Err(e) = fs::mount_initramfs("/") {
+    warn!("initramfs not mounted: {}", e);
+  }
+
+  if let Err(e) = fs::mount_procfs("/proc") {
+    warn!("procfs not mounted: {}", e);
+  }
+
+  // There is no boot partition without an SD card; e.g. in QEMU
+  if let Err(e) = fs::mount_fat32(bsp::BOOT_PARTITION, "/boot") {
+    warn!("Boot partition not mounted: {}", e);
+  }
+
+  // Starts the timer tick; which only preempts once IRQs are unmasked
+  if let Err(e) = thread::init() {
+    panic!("Error initializing threads: {}", e);
+  }
+
+  if let Err(e) = executor::init() {
+    panic!("Error initializing the async executor: {}", e);
+  }
+
+  // Drivers have registered their IRQ handlers; let the interrupts in
+  exception::asynchronous::local_irq_unmask();
+
   // Transition unsafe -> safe
   kernel_main()
 }
 
 /// main kernel function
 fn kernel_main() -> ! {
-  use console::console;
   use core::time::Duration;
 
   info!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
   info!("Booting on: {}", bsp::board_name());
+  bsp::board::print_info();
 
   info!("MMU online; special regions:");
   bsp::memory::mmu::virt_mem_layout().print_layout();
@@ -78,6 +127,12 @@ fn kernel_main() -> ! {
   info!("Drivers loaded:");
   driver::driver_manager().enumerate();
 
+  info!("Registered IRQ handlers:");
+  exception::asynchronous::irq_manager().print_handler();
+
+  info!("Console statistics:");
+  console::print_statistics();
+
   info!("Timer test: spinning for 1 second");
   time::time_manager().spin_for(Duration::from_secs(1));
 
@@ -94,21 +149,6 @@ fn kernel_main() -> ! {
   info!("WHOA! We recovered from a synchronous exception!");
   info!("************************************************");
   info!("");
-  info!("... let's try again");
 
-  // Now use address 9 GiB
-  // The exception handler won't forgive us this time...
-  info!("Trying to read from address 9 GiB");
-  let big_addr: u64 = 9 * 1024 * 1024 * 1024;
-  unsafe { core::ptr::read_volatile(big_addr as *mut u64) };
-  
-  // We won't get here right now
-  info!("Echoing input now");
-
-  // Discard any spurious received characters before going into echo mode
-  console().clear_rx();
-  loop {
-    let c = console().read_char();
-    console().write_char(c);
-  }
+  shell::run()
 }
\ No newline at end of file
@@ -20,7 +20,13 @@ use core::{
   ops::RangeInclusive,
 };
 
-use crate::common;
+use crate::{
+  common,
+  log::{
+    Level,
+    LineLogger,
+  },
+};
 
 pub use arch_mmu::mmu;
 
@@ -68,6 +74,8 @@ pub enum Translation {
 #[derive(Copy, Clone)]
 pub enum MemAttributes {
   CacheableDRAM,
+  /// DRAM shared with other bus masters; e.g. the VideoCore
+  NonCacheableDRAM,
   Device,
 }
 
@@ -160,13 +168,14 @@ impl fmt::Display for TranslationDescriptor {
     // which causes Rust to copy the value
     let start = *(self.virtual_range)().start();
     let end   = *(self.virtual_range)().end();
-    let size  = end - start + 1;
+    let size  = (end + 1).saturating_sub(start);
 
     let (size, unit) = common::size_human_readable_ceil(size);
 
     let attr = match self.attribute_fields.mem_attributes {
-      MemAttributes::CacheableDRAM => "C",
-      MemAttributes::Device        => "Dev",
+      MemAttributes::CacheableDRAM    => "C",
+      MemAttributes::NonCacheableDRAM => "NC",
+      MemAttributes::Device           => "Dev",
     };
 
     let acc_p = match self.attribute_fields.acc_perms {
@@ -216,8 +225,13 @@ impl<const NUM_SPECIAL_RANGES:usize> KernelVirtualLayout<{ NUM_SPECIAL_RANGES }>
 
   /// Print the memory layout
   pub fn print_layout(&self) {
-    use crate::info;
+    let _ = self.write_layout(&mut LineLogger::new(Level::Info, module_path!(), ""));
+  }
+
+  /// Write the memory layout to `w`; one region per line
+  pub fn write_layout(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+    for i in self.inner.iter() { writeln!(w, "{}", i)?; }
 
-    for i in self.inner.iter() { info!("{}", i); }
+    Ok(())
   }
 }
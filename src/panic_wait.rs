@@ -2,9 +2,9 @@
 //
 // Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
 
-//! A panic handler that infinitely waits.
+//! A panic handler that infinitely waits; or reboots the board after the delay set with `power::set_panic_reboot_delay`
 
-use crate::{cpu, println};
+use crate::{console::{self, ansi}, cpu, power, println, time};
 use core::panic::PanicInfo;
 
 fn panic_prevent_reenter() {
@@ -35,14 +35,29 @@ fn panic(info: &PanicInfo) -> ! {
     };
 
     println!(
-        "[  {:>3}.{:06}] Kernel Panic!\n\nPanic location:\n\tFile: {}, line {}, column {},\n\n{}",
+        "[  {:>3}.{:06}] {}{}Kernel Panic!{}\n\nPanic location:\n\tFile: {}, line {}, column {},\n\n{}",
         timestamp.as_secs(),
         timestamp.subsec_micros(),
+        ansi::BOLD,
+        ansi::Fg(ansi::Color::Red),
+        ansi::RESET,
         location,
         line,
         column,
         info.message(),
     );
 
-    cpu::wait_forever()
+    let Some(delay) = power::panic_reboot_delay() else {
+        // Make sure the message is fully out on the wire before parking the core
+        console::console().flush();
+
+        cpu::wait_forever()
+    };
+
+    println!("Rebooting in {}.{:03}s", delay.as_secs(), delay.subsec_millis());
+    console::console().flush();
+
+    time::time_manager().spin_for(delay);
+
+    power::power_manager().reboot()
 }
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! System power control
+//!
+//! The BSP registers a power manager; until it does, rebooting and halting just park the core and there is no watchdog
+
+mod commands;
+mod null_power_manager;
+
+use core::time::Duration;
+
+use crate::{
+  shell,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+};
+
+pub mod interface {
+  use core::time::Duration;
+
+  /// Power state functions
+  pub trait PowerManager {
+    /// Reset the board
+    fn reboot(&self) -> !;
+
+    /// Power down as far as the board allows
+    fn halt(&self) -> !;
+  }
+
+  /// Hardware watchdog functions
+  pub trait Watchdog {
+    /// Reset the board unless kicked within `timeout`
+    fn start(&self, timeout: Duration) -> Result<(), &'static str>;
+
+    /// Restart the countdown of a running watchdog
+    fn kick(&self);
+
+    /// Stop the watchdog
+    fn stop(&self);
+  }
+
+  pub trait All: PowerManager + Watchdog {}
+}
+
+static CUR_POWER_MANAGER: NullLock<&'static (dyn interface::All + Sync)> =
+  NullLock::new(&null_power_manager::NULL_POWER_MANAGER);
+
+/// How long a panic waits before rebooting the board; `None` to wait forever
+static PANIC_REBOOT_DELAY: NullLock<Option<Duration>> = NullLock::new(None);
+
+/// Register the power shell commands
+pub fn init() -> Result<(), &'static str> {
+  shell::register_commands(&commands::COMMANDS)
+}
+
+/// Register a new power manager
+pub fn register_power_manager(new_manager: &'static (dyn interface::All + Sync)) {
+  CUR_POWER_MANAGER.lock(|manager| *manager = new_manager);
+}
+
+/// Return a reference to the currently registered power manager
+pub fn power_manager() -> &'static (dyn interface::All + Sync) {
+  CUR_POWER_MANAGER.lock(|manager| *manager)
+}
+
+/// Have a panic reboot the board after `delay`; or wait forever with `None`
+pub fn set_panic_reboot_delay(delay: Option<Duration>) {
+  PANIC_REBOOT_DELAY.lock(|d| *d = delay);
+}
+
+/// How long a panic waits before rebooting the board
+pub fn panic_reboot_delay() -> Option<Duration> {
+  PANIC_REBOOT_DELAY.lock(|d| *d)
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Power control shell commands
+
+use core::{
+  str::SplitWhitespace,
+  time::Duration,
+};
+
+use crate::{
+  console,
+  power,
+  println,
+  shell::{
+    parse_number,
+    Command,
+  },
+};
+
+pub const COMMANDS: [Command; 4] = [
+  Command::new("reboot",   "Reboot the board",                                  reboot),
+  Command::new("halt",     "Power down the board",                              halt),
+  Command::new("watchdog", "watchdog start <secs>|kick|stop: HW watchdog",      watchdog),
+  Command::new("onpanic",  "onpanic [hang|reboot <secs>]: Panic policy",        onpanic),
+];
+
+fn reboot(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  console::console().flush();
+
+  power::power_manager().reboot()
+}
+
+fn halt(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  console::console().flush();
+
+  power::power_manager().halt()
+}
+
+fn watchdog(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let watchdog = power::power_manager();
+
+  match args.next() {
+    Some("start") => watchdog.start(Duration::from_secs(parse_number(args.next())? as u64))?,
+    Some("kick")  => watchdog.kick(),
+    Some("stop")  => watchdog.stop(),
+    _             => return Err("Expected start, kick or stop"),
+  }
+
+  Ok(())
+}
+
+fn onpanic(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  match args.next() {
+    Some("hang")   => power::set_panic_reboot_delay(None),
+    Some("reboot") => power::set_panic_reboot_delay(Some(Duration::from_secs(parse_number(args.next())? as u64))),
+    Some(_)        => return Err("Expected hang or reboot"),
+    None           => (),
+  }
+
+  match power::panic_reboot_delay() {
+    Some(delay) => println!("reboot after {}s", delay.as_secs()),
+    None        => println!("hang"),
+  }
+
+  Ok(())
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! A power manager that can only park the core
+
+use core::time::Duration;
+
+use super::interface;
+use crate::cpu;
+
+pub struct NullPowerManager;
+
+pub static NULL_POWER_MANAGER: NullPowerManager = NullPowerManager {};
+
+impl interface::PowerManager for NullPowerManager {
+  fn reboot(&self) -> ! {
+    cpu::wait_forever()
+  }
+
+  fn halt(&self) -> ! {
+    cpu::wait_forever()
+  }
+}
+
+impl interface::Watchdog for NullPowerManager {
+  fn start(&self, _timeout: Duration) -> Result<(), &'static str> {
+    Err("No power manager registered yet")
+  }
+
+  fn kick(&self) {}
+
+  fn stop(&self) {}
+}
+
+impl interface::All for NullPowerManager {}
+
+unsafe impl Sync for NullPowerManager {}
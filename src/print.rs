@@ -29,50 +29,48 @@ macro_rules! println {
     })
 }
 
-/// Prints an info message; with a newline
+/// Logs a message at the given level; with a newline
+///
+/// Records above `log::STATIC_MAX_LEVEL` compile to nothing
 #[macro_export]
-macro_rules! info {
-    ($string:expr) => {
-        let timestamp = $crate::time::time_manager().uptime();
-
-        $crate::print::_print(format_args_nl!(
-          concat!("[  {:>3}.{:06}] ", $string),
-          timestamp.as_secs(),
-          timestamp.subsec_micros(),
-        ))
-    };
-    ($format_string:expr, $($arg:tt)*) => ({
-        let timestamp = $crate::time::time_manager().uptime();
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => ({
+        let level = $level;
 
-        $crate::print::_print(format_args_nl!(
-          concat!("[  {:>3}.{:06}] ", $format_string),
-          timestamp.as_secs(),
-          timestamp.subsec_micros(),
-          $($arg)*
-        ))
+        if level <= $crate::log::STATIC_MAX_LEVEL {
+            $crate::log::_log(level, module_path!(), format_args!($($arg)*));
+        }
     })
 }
 
-/// Prints a warning message; with a newline
+/// Logs an error message; with a newline
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => ($crate::log!($crate::log::Level::Error, $($arg)*));
+}
+
+/// Logs a warning message; with a newline
 #[macro_export]
 macro_rules! warn {
-    ($string:expr) => {
-        let timestamp = $crate::time::time_manager().uptime();
+    ($($arg:tt)*) => ($crate::log!($crate::log::Level::Warn, $($arg)*));
+}
 
-        $crate::print::_print(format_args_nl!(
-          concat!("[  {:>3}.{:06}] ", $string),
-          timestamp.as_secs(),
-          timestamp.subsec_micros(),
-        ))
-    };
-    ($format_string:expr, $($arg:tt)*) => ({
-        let timestamp = $crate::time::time_manager().uptime();
+/// Logs an info message; with a newline
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => ($crate::log!($crate::log::Level::Info, $($arg)*));
+}
 
-        $crate::print::_print(format_args_nl!(
-          concat!("[  {:>3}.{:06}] ", $format_string),
-          timestamp.as_secs(),
-          timestamp.subsec_micros(),
-          $($arg)*
-        ))
-    })
+/// Logs a debug message; with a newline
+/// Stripped from release builds
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => ($crate::log!($crate::log::Level::Debug, $($arg)*));
+}
+
+/// Logs a trace message; with a newline
+/// Stripped from release builds
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => ($crate::log!($crate::log::Level::Trace, $($arg)*));
 }
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Random numbers
+//!
+//! Bytes come from the BSP's hardware entropy source when it has one
+//! Otherwise they come from a ChaCha20 CSPRNG; seeded from the entropy source when it gets registered, or from the board serial and timer jitter if there never is one
+//!
+//! Without a hardware entropy source the output is predictable; it must not be relied on for secrets then
+
+mod chacha;
+mod null_entropy_source;
+
+use core::time::Duration;
+
+use crate::{
+  bsp,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+    NullLock,
+  },
+  time,
+};
+
+use chacha::ChaCha20Rng;
+
+pub mod interface {
+  /// Entropy source functions
+  pub trait EntropySource {
+    /// Fill `buf` with random bytes
+    fn fill_bytes(&self, buf: &mut [u8]) -> Result<(), &'static str>;
+  }
+}
+
+static CUR_ENTROPY_SOURCE: NullLock<&'static (dyn interface::EntropySource + Sync)> =
+  NullLock::new(&null_entropy_source::NULL_ENTROPY_SOURCE);
+
+static FALLBACK: IRQSafeNullLock<ChaCha20Rng> = IRQSafeNullLock::new(ChaCha20Rng::new());
+
+/// The number of timer samples folded into each word of the fallback seed
+const SAMPLES_PER_WORD: usize = 16;
+
+/// The spin between two timer samples; long enough for the time it actually takes to vary
+const SAMPLE_SPACING: Duration = Duration::from_micros(10);
+
+/// A weak seed for when there is no entropy source; from the board serial and timer jitter
+///
+/// The serial only sets boards apart, and is no secret; each timer sample adds a few bits at best, as the spins vary in length by a few timer ticks
+/// So the seed can be guessed with moderate effort; the CSPRNG's output is predictable
+fn fallback_seed() -> [u8; chacha::SEED_SIZE] {
+  let mut seed = [0; chacha::SEED_SIZE];
+  let (serial, jitter) = seed.split_at_mut(8);
+
+  serial.copy_from_slice(&bsp::board::serial().unwrap_or(0).to_le_bytes());
+
+  for chunk in jitter.chunks_mut(4) {
+    let mut word = 0u32;
+
+    for _ in 0..SAMPLES_PER_WORD {
+      time::time_manager().spin_for(SAMPLE_SPACING);
+
+      word = word.rotate_left(5) ^ time::time_manager().uptime().subsec_nanos();
+    }
+
+    chunk.copy_from_slice(&word.to_le_bytes());
+  }
+
+  seed
+}
+
+/// Register a new entropy source; it also seeds the CSPRNG
+pub fn register_entropy_source(new_source: &'static (dyn interface::EntropySource + Sync)) -> Result<(), &'static str> {
+  let mut seed = [0; chacha::SEED_SIZE];
+
+  new_source.fill_bytes(&mut seed)?;
+
+  FALLBACK.lock(|rng| rng.seed(&seed));
+  CUR_ENTROPY_SOURCE.lock(|source| *source = new_source);
+
+  Ok(())
+}
+
+/// Fill `buf` with random bytes
+pub fn fill_bytes(buf: &mut [u8]) {
+  let source = CUR_ENTROPY_SOURCE.lock(|source| *source);
+
+  if source.fill_bytes(buf).is_ok() { return; }
+
+  // Seeding takes a while; so not with IRQs masked
+  if !FALLBACK.lock(|rng| rng.is_seeded()) {
+    let seed = fallback_seed();
+
+    FALLBACK.lock(|rng| if !rng.is_seeded() { rng.seed(&seed); });
+  }
+
+  FALLBACK.lock(|rng| rng.fill_bytes(buf));
+}
+
+/// A random `u64`
+#[allow(dead_code)]
+pub fn next_u64() -> u64 {
+  let mut bytes = [0; 8];
+
+  fill_bytes(&mut bytes);
+
+  u64::from_le_bytes(bytes)
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! ChaCha20 CSPRNG
+//!
+//! The keystream of ChaCha20 (RFC 8439) with a zero nonce; the key is the seed
+//! Reseeding mixes the new seed into the current key, so a weak seed never replaces a strong one
+
+/// The seed size in bytes
+pub const SEED_SIZE: usize = 32;
+
+/// "expand 32-byte k"
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+const BLOCK_SIZE: usize = 64;
+
+pub struct ChaCha20Rng {
+  key: [u32; 8],
+  counter: u64,
+  seeded: bool,
+
+  /// The current keystream block; bytes before `pos` were handed out already
+  block: [u8; BLOCK_SIZE],
+  pos: usize,
+}
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+  s[a] = s[a].wrapping_add(s[b]); s[d] = (s[d] ^ s[a]).rotate_left(16);
+  s[c] = s[c].wrapping_add(s[d]); s[b] = (s[b] ^ s[c]).rotate_left(12);
+  s[a] = s[a].wrapping_add(s[b]); s[d] = (s[d] ^ s[a]).rotate_left(8);
+  s[c] = s[c].wrapping_add(s[d]); s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+impl ChaCha20Rng {
+  /// Create an unseeded instance
+  pub const fn new() -> Self {
+    Self {
+      key: [0; 8],
+      counter: 0,
+      seeded: false,
+      block: [0; BLOCK_SIZE],
+      pos: BLOCK_SIZE,
+    }
+  }
+
+  pub fn is_seeded(&self) -> bool {
+    self.seeded
+  }
+
+  /// Mix `seed` into the key; and drop what is left of the current block
+  pub fn seed(&mut self, seed: &[u8; SEED_SIZE]) {
+    for (word, bytes) in self.key.iter_mut().zip(seed.chunks_exact(4)) {
+      *word ^= u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    self.seeded = true;
+    self.pos = BLOCK_SIZE;
+  }
+
+  /// Generate the next keystream block
+  fn refill(&mut self) {
+    let mut state = [0; 16];
+
+    state[..4].copy_from_slice(&CONSTANTS);
+    state[4..12].copy_from_slice(&self.key);
+    state[12] = self.counter as u32;
+    state[13] = (self.counter >> 32) as u32;
+
+    let mut working = state;
+
+    for _ in 0..10 {
+      quarter_round(&mut working, 0, 4,  8, 12);
+      quarter_round(&mut working, 1, 5,  9, 13);
+      quarter_round(&mut working, 2, 6, 10, 14);
+      quarter_round(&mut working, 3, 7, 11, 15);
+      quarter_round(&mut working, 0, 5, 10, 15);
+      quarter_round(&mut working, 1, 6, 11, 12);
+      quarter_round(&mut working, 2, 7,  8, 13);
+      quarter_round(&mut working, 3, 4,  9, 14);
+    }
+
+    for (i, bytes) in self.block.chunks_exact_mut(4).enumerate() {
+      bytes.copy_from_slice(&working[i].wrapping_add(state[i]).to_le_bytes());
+    }
+
+    self.counter = self.counter.wrapping_add(1);
+    self.pos = 0;
+  }
+
+  /// Fill `buf` with keystream bytes
+  pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+    let mut filled = 0;
+
+    while filled < buf.len() {
+      if self.pos == BLOCK_SIZE { self.refill(); }
+
+      let n = (buf.len() - filled).min(BLOCK_SIZE - self.pos);
+
+      buf[filled..filled + n].copy_from_slice(&self.block[self.pos..self.pos + n]);
+
+      filled += n;
+      self.pos += n;
+    }
+  }
+}
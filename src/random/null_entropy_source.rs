@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! An entropy source without any entropy
+
+use super::interface;
+
+pub struct NullEntropySource;
+
+pub static NULL_ENTROPY_SOURCE: NullEntropySource = NullEntropySource {};
+
+impl interface::EntropySource for NullEntropySource {
+  fn fill_bytes(&self, _buf: &mut [u8]) -> Result<(), &'static str> {
+    Err("No entropy source registered yet")
+  }
+}
+
+unsafe impl Sync for NullEntropySource {}
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Interactive kernel debug shell
+//!
+//! Reads lines from the console and dispatches them to registered commands
+//! Subsystems can add their own commands through `register_command`
+
+mod commands;
+
+use core::str::SplitWhitespace;
+
+use crate::{
+  console,
+  print,
+  println,
+  synchronization::{
+    interface::Mutex,
+    NullLock,
+  },
+};
+
+/// Room for the subsystems' commands on top of the built-ins
+const NUM_COMMANDS: usize = commands::NUM_BUILTINS + 48;
+const LINE_LENGTH:  usize = 128;
+const PROMPT:       &str  = "hos> ";
+
+/// A command's implementation; gets the arguments following the command name
+pub type CommandFn = fn(args: &mut SplitWhitespace) -> Result<(), &'static str>;
+
+/// Describes a shell command
+#[derive(Copy, Clone)]
+pub struct Command {
+  name: &'static str,
+  help: &'static str,
+  run: CommandFn,
+}
+
+struct ShellInner {
+  commands: [Option<Command>; NUM_COMMANDS],
+}
+
+static SHELL: NullLock<ShellInner> = NullLock::new(ShellInner::new());
+
+impl Command {
+  /// Create an instance
+  pub const fn new(name: &'static str, help: &'static str, run: CommandFn) -> Self {
+    Self {
+      name,
+      help,
+      run,
+    }
+  }
+}
+
+impl ShellInner {
+  /// Create an instance
+  pub const fn new() -> Self {
+    Self {
+      commands: [None; NUM_COMMANDS],
+    }
+  }
+
+  fn find(&self, name: &str) -> Option<Command> {
+    self.commands.iter().flatten().find(|c| c.name == name).copied()
+  }
+
+  fn register(&mut self, command: Command) -> Result<(), &'static str> {
+    if self.find(command.name).is_some() { return Err("Command already registered"); }
+
+    match self.commands.iter_mut().find(|c| c.is_none()) {
+      Some(free) => {
+        *free = Some(command);
+
+        Ok(())
+      }
+      None => Err("No free command slots"),
+    }
+  }
+}
+
+/// Add a command to the shell
+pub fn register_command(command: Command) -> Result<(), &'static str> {
+  SHELL.lock(|s| s.register(command))
+}
+
+/// Add several commands to the shell; e.g. a subsystem's from its init
+pub fn register_commands(commands: &[Command]) -> Result<(), &'static str> {
+  commands.iter().try_for_each(|&command| register_command(command))
+}
+
+/// Parse an address or value; hexadecimal with a `0x` prefix, decimal otherwise
+pub fn parse_number(arg: Option<&str>) -> Result<usize, &'static str> {
+  let arg = arg.ok_or("Missing argument")?;
+
+  let parsed = match arg.strip_prefix("0x") {
+    Some(hex) => usize::from_str_radix(hex, 16),
+    None      => arg.parse(),
+  };
+
+  parsed.map_err(|_| "Invalid number")
+}
+
+/// Call `f` for every registered command
+fn for_each_command(f: impl FnMut(&Command)) {
+  SHELL.lock(|s| s.commands.iter().flatten().for_each(f));
+}
+
+/// Run a single line of input
+fn execute(line: &str) {
+  let mut args = line.split_whitespace();
+
+  let Some(name) = args.next() else { return; };
+
+  match SHELL.lock(|s| s.find(name)) {
+    Some(command) => {
+      if let Err(e) = (command.run)(&mut args) { println!("{}: {}", name, e); }
+    }
+    None => println!("{}: command not found; try `help`", name),
+  }
+}
+
+/// Register the built-in commands; before the subsystems register theirs, so `help` lists the built-ins first
+pub fn init() {
+  commands::register_builtins();
+}
+
+/// Run the shell; never returns
+pub fn run() -> ! {
+  println!("Kernel debug shell; type `help` for a list of commands");
+
+  // Discard any spurious received characters before reading commands
+  console::console().clear_rx();
+
+  let mut buf = [0u8; LINE_LENGTH];
+
+  loop {
+    print!("{}", PROMPT);
+
+    match console::read_line(&mut buf) {
+      Ok(line) => execute(line),
+      Err(_)   => continue,
+    }
+  }
+}
@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Built-in shell commands
+
+use core::str::SplitWhitespace;
+
+use super::{
+  Command,
+  for_each_command,
+  parse_number,
+  register_command,
+};
+use crate::{
+  bsp,
+  console::{
+    self,
+    ansi,
+  },
+  driver,
+  exception,
+  log,
+  print,
+  println,
+  random,
+  time,
+};
+
+/// How many built-in commands there are
+pub const NUM_BUILTINS: usize = 15;
+
+const BUILTINS: [Command; NUM_BUILTINS] = [
+  Command::new("help",     "List all commands",                                 help),
+  Command::new("uptime",   "Time since power-on",                               uptime),
+  Command::new("drivers",  "List the loaded drivers",                           drivers),
+  Command::new("layout",   "Print the kernel's special memory regions",         layout),
+  Command::new("el",       "Print the current privilege level",                 el),
+  Command::new("daif",     "Print the exception mask state",                    daif),
+  Command::new("peek",     "peek <addr>: Read a u32 from memory",               peek),
+  Command::new("poke",     "poke <addr> <value>: Write a u32 to memory",        poke),
+  Command::new("dmesg",    "Print the kernel log",                              dmesg),
+  Command::new("firmware", "Print the board details reported by the firmware",  firmware),
+  Command::new("stats",    "Print console statistics",                          stats),
+  Command::new("stty",     "stty [raw|cooked|[no]echo|<baud>|8n1]...: Console", stty),
+  Command::new("loglevel", "loglevel [error|warn|info|debug|trace]: Log level", loglevel),
+  Command::new("clear",    "Clear the screen",                                  clear),
+  Command::new("random",   "random [count]: Print random bytes",                random),
+];
+
+/// Register all built-in commands
+pub fn register_builtins() {
+  for command in BUILTINS {
+    // Built-ins are registered once, before anything else is; this cannot fail
+    register_command(command).unwrap();
+  }
+}
+
+/// Parse an address that must be suitably aligned for a u32 access
+fn parse_address(arg: Option<&str>) -> Result<usize, &'static str> {
+  let addr = parse_number(arg)?;
+
+  if !addr.is_multiple_of(4) { return Err("Address must be 4-byte aligned"); }
+
+  Ok(addr)
+}
+
+fn help(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  for_each_command(|c| println!("  {:<10} {}", c.name, c.help));
+
+  Ok(())
+}
+
+fn uptime(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let uptime = time::time_manager().uptime();
+
+  println!("{}.{:06}s", uptime.as_secs(), uptime.subsec_micros());
+
+  Ok(())
+}
+
+fn drivers(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  driver::driver_manager().enumerate();
+
+  Ok(())
+}
+
+fn layout(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  bsp::memory::mmu::virt_mem_layout().print_layout();
+
+  Ok(())
+}
+
+fn el(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let (_, privilege_level) = exception::current_privilege_level();
+
+  println!("{}", privilege_level);
+
+  Ok(())
+}
+
+fn daif(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  exception::asynchronous::print_state();
+
+  Ok(())
+}
+
+/// Accessing an unmapped address raises a synchronous exception
+fn peek(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let addr = parse_address(args.next())?;
+  let value = unsafe { core::ptr::read_volatile(addr as *const u32) };
+
+  println!("{:#010x}: {:#010x}", addr, value);
+
+  Ok(())
+}
+
+/// Accessing an unmapped or read-only address raises a synchronous exception
+fn poke(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let addr = parse_address(args.next())?;
+  let value = u32::try_from(parse_number(args.next())?).map_err(|_| "Value does not fit in a u32")?;
+
+  unsafe { core::ptr::write_volatile(addr as *mut u32, value) };
+
+  Ok(())
+}
+
+fn dmesg(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  log::dump();
+
+  Ok(())
+}
+
+fn firmware(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  bsp::firmware::print_info();
+
+  Ok(())
+}
+
+fn stats(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  console::print_statistics();
+
+  Ok(())
+}
+
+fn loglevel(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  if let Some(level) = args.next() {
+    log::set_max_level(level.parse()?);
+  }
+
+  println!("{}", log::max_level());
+
+  Ok(())
+}
+
+/// Numbers set the console UART's baud rate; e.g. `stty 115200 8n1`
+fn stty(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let discipline = console::line_discipline();
+  let mut uart_config = bsp::driver::console_uart_config();
+
+  for arg in args {
+    match arg {
+      "raw"    => discipline.set_mode(console::Mode::Raw),
+      "cooked" => discipline.set_mode(console::Mode::Canonical),
+      "echo"   => discipline.set_echo(true),
+      "noecho" => discipline.set_echo(false),
+      _        => match arg.parse() {
+        Ok(baud) => uart_config.baud = baud,
+        Err(_)   => uart_config = uart_config.with_frame(arg)?,
+      },
+    }
+  }
+
+  // All at once, and only if changed; reprogramming the UART drops what it has received
+  if uart_config != bsp::driver::console_uart_config() { bsp::driver::set_console_uart_config(uart_config)?; }
+
+  let mode = match discipline.mode() {
+    console::Mode::Canonical => "cooked",
+    console::Mode::Raw       => "raw",
+  };
+
+  println!("{} {} {}", mode, if discipline.echo() { "echo" } else { "noecho" }, uart_config);
+
+  Ok(())
+}
+
+fn clear(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  print!("{}{}", ansi::CLEAR_SCREEN, ansi::CursorTo { row: 1, col: 1 });
+
+  Ok(())
+}
+
+fn random(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let mut bytes = [0; 64];
+  let count = match args.next() {
+    Some(arg) => parse_number(Some(arg))?,
+    None      => 16,
+  };
+
+  let bytes = bytes.get_mut(..count).ok_or("At most 64 bytes")?;
+
+  random::fill_bytes(bytes);
+
+  for b in bytes.iter() { print!("{:02x}", b); }
+  println!();
+
+  Ok(())
+}
+
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Kernel symbol lookup
+//!
+//! The linker script reserves the `.kernel_symbols` section
+//! After linking, `tools/kernel_symbols.rb` extracts the function symbols from the kernel ELF and patches them into that section
+//! If the tool did not run (e.g. the raw ELF straight out of `cargo`) the section is all zeroes and every lookup returns `None`
+
+use core::{
+  cell::UnsafeCell,
+  fmt,
+  mem::size_of,
+  slice,
+};
+
+// Symbols from the linker script
+unsafe extern "Rust" {
+  static __kernel_symbols_start:         UnsafeCell<()>;
+  static __kernel_symbols_end_exclusive: UnsafeCell<()>;
+}
+
+/// "KSYM"; must match `MAGIC` in `tools/kernel_symbols.rb`
+const MAGIC: u32 = 0x4D59_534B;
+
+/// Table header, as written by `tools/kernel_symbols.rb`
+#[repr(C)]
+struct Header {
+  magic: u32,
+  num_entries: u32,
+  strtab_offset: u32,
+  strtab_size: u32,
+}
+
+/// A single function symbol; entries are sorted by `start`
+#[repr(C)]
+struct Entry {
+  start: u64,
+  size: u32,
+  name_offset: u32,
+}
+
+/// The parsed symbol table
+struct SymbolTable {
+  entries: &'static [Entry],
+  strtab: &'static [u8],
+}
+
+/// An address that displays as `0x... (function+0x1c)` when its symbol is known
+pub struct Symbolized(pub usize);
+
+/// The raw bytes of the `.kernel_symbols` section
+///
+/// # Safety
+///
+/// Values are provided by the linker script and must be trusted as-is
+fn section() -> &'static [u8] {
+  let start = unsafe { __kernel_symbols_start.get() as usize };
+  let end   = unsafe { __kernel_symbols_end_exclusive.get() as usize };
+
+  unsafe { slice::from_raw_parts(start as *const u8, end - start) }
+}
+
+/// Parse the section; `None` if it holds no (valid) table
+fn symbol_table() -> Option<SymbolTable> {
+  let section = section();
+
+  if section.len() < size_of::<Header>() { return None; }
+
+  // The section is 8-byte aligned by the linker script; so are the header and entries
+  let header = unsafe { &*(section.as_ptr() as *const Header) };
+
+  if header.magic != MAGIC { return None; }
+
+  let entries_end = size_of::<Header>() + header.num_entries as usize * size_of::<Entry>();
+  let strtab_start = header.strtab_offset as usize;
+  let strtab_end = strtab_start + header.strtab_size as usize;
+
+  if entries_end > strtab_start || strtab_end > section.len() { return None; }
+
+  let entries = unsafe {
+    slice::from_raw_parts(
+      section.as_ptr().add(size_of::<Header>()) as *const Entry,
+      header.num_entries as usize,
+    )
+  };
+
+  Some(SymbolTable {
+    entries,
+    strtab: &section[strtab_start..strtab_end],
+  })
+}
+
+impl SymbolTable {
+  /// The NUL-terminated name at `offset` in the string table
+  fn name(&self, offset: u32) -> Option<&'static str> {
+    let name = self.strtab.get(offset as usize..)?;
+    let len = name.iter().position(|&b| b == 0)?;
+
+    core::str::from_utf8(&name[..len]).ok()
+  }
+
+  fn lookup(&self, addr: usize) -> Option<(&'static str, usize)> {
+    let addr = addr as u64;
+
+    // Index of the last entry starting at or before `addr`
+    let i = self.entries.partition_point(|e| e.start <= addr).checked_sub(1)?;
+    let entry = &self.entries[i];
+
+    if addr >= entry.start + entry.size as u64 { return None; }
+
+    Some((self.name(entry.name_offset)?, (addr - entry.start) as usize))
+  }
+}
+
+/// Find the function containing `addr`
+///
+/// Returns the function's name and the offset of `addr` from the function's start
+pub fn lookup(addr: usize) -> Option<(&'static str, usize)> {
+  symbol_table()?.lookup(addr)
+}
+
+impl fmt::Display for Symbolized {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:#018x}", self.0)?;
+
+    match lookup(self.0) {
+      Some((name, offset)) => write!(f, " ({}+{:#x})", name, offset),
+      None                 => Ok(()),
+    }
+  }
+}
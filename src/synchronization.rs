@@ -4,6 +4,11 @@
 
 use core::cell::SyncUnsafeCell;
 
+use crate::{
+  exception::asynchronous::exec_with_irq_masked,
+  thread,
+};
+
 pub mod interface {
   pub trait Mutex {
     type Data;
@@ -17,6 +22,14 @@ where T: ?Sized {
   data: SyncUnsafeCell<T>,
 }
 
+/// A lock that masks IRQs on the executing core while it is held
+///
+/// Data that is touched from IRQ handlers must be protected by this lock; otherwise an IRQ could interrupt a holder of the lock
+pub struct IRQSafeNullLock<T>
+where T: ?Sized {
+  data: SyncUnsafeCell<T>,
+}
+
 impl<T> NullLock<T> {
   pub const fn new(data: T) -> Self {
     Self {
@@ -31,6 +44,29 @@ impl<T> interface::Mutex for NullLock<T> {
   fn lock<'a, R>(&'a self, f: impl FnOnce(&'a mut Self::Data) -> R) -> R {
     let data = unsafe { &mut *self.data.get() };
 
-    f(data)
+    // Another thread must not get to the data while it is borrowed
+    thread::preempt_disable();
+    let ret = f(data);
+    thread::preempt_enable();
+
+    ret
+  }
+}
+
+impl<T> IRQSafeNullLock<T> {
+  pub const fn new(data: T) -> Self {
+    Self {
+      data: SyncUnsafeCell::new(data),
+    }
+  }
+}
+
+impl<T> interface::Mutex for IRQSafeNullLock<T> {
+  type Data = T;
+
+  fn lock<'a, R>(&'a self, f: impl FnOnce(&'a mut Self::Data) -> R) -> R {
+    let data = unsafe { &mut *self.data.get() };
+
+    exec_with_irq_masked(|| f(data))
   }
-}
\ No newline at end of file
+}
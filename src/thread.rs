@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Kernel threads
+//!
+//! Every thread has its own stack and saved register context; all of them run at EL1
+//! A thread is switched away from when it yields, sleeps or exits; or when the timer tick finds its time slice used up
+//! Ready threads take turns in slot order (round robin); the idle thread only runs when none is ready
+//!
+//! The flow that calls `init` becomes the `main` thread and keeps the boot stack; the other stacks are static
+//! A thread's slot and stack are free again once it has exited
+//!
+//! Data behind a `NullLock` must not be touched by two threads at once; so there is no preemption while one is held
+//! Switching away voluntarily while holding one is a bug
+
+#[cfg(target_arch = "aarch64")]
+#[path = "_arch/aarch64/thread.rs"]
+mod arch_thread;
+mod commands;
+
+use core::{
+  cell::SyncUnsafeCell,
+  fmt,
+  sync::atomic::{
+    AtomicBool,
+    AtomicUsize,
+    Ordering,
+  },
+  time::Duration,
+};
+
+use arch_thread::Context;
+
+use crate::{
+  cpu,
+  exception::asynchronous::{
+    exec_with_irq_masked,
+    local_irq_unmask,
+  },
+  shell,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+  },
+  time,
+};
+
+const NUM_THREADS: usize = 8;
+const STACK_SIZE:  usize = 16 * 1024;
+
+/// Slots with a fixed purpose
+const MAIN: usize = 0;
+const IDLE: usize = 1;
+
+/// What a thread is doing
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum State {
+  /// The slot is unused
+  Free,
+  Ready,
+  Running,
+
+  /// Until the uptime reaches the given value
+  Sleeping(Duration),
+}
+
+struct Thread {
+  name: &'static str,
+  state: State,
+  entry: Option<fn()>,
+  context: Context,
+}
+
+struct SchedulerInner {
+  threads: [Thread; NUM_THREADS],
+  current: usize,
+
+  /// Set by the timer tick; the running thread's time slice is used up
+  need_resched: bool,
+}
+
+/// Only ever used through the stack pointer
+#[allow(dead_code)]
+#[repr(align(16))]
+struct Stack([u8; STACK_SIZE]);
+
+/// The stacks of all threads but `main`
+static STACKS: SyncUnsafeCell<[Stack; NUM_THREADS - 1]> =
+  SyncUnsafeCell::new([const { Stack([0; STACK_SIZE]) }; NUM_THREADS - 1]);
+
+static SCHEDULER: IRQSafeNullLock<SchedulerInner> = IRQSafeNullLock::new(SchedulerInner::new());
+
+/// Until `init` there is only the boot flow; nothing to switch to
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// The number of `NullLock`s the running thread holds
+///
+/// Only ever loaded and stored; atomic read-modify-write instructions don't work before the MMU is on
+static PREEMPT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+impl fmt::Display for State {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      State::Free        => write!(f, "free"),
+      State::Ready       => write!(f, "ready"),
+      State::Running     => write!(f, "running"),
+      State::Sleeping(_) => write!(f, "sleeping"),
+    }
+  }
+}
+
+impl Thread {
+  const fn new() -> Self {
+    Self {
+      name: "",
+      state: State::Free,
+      entry: None,
+      context: Context::new(),
+    }
+  }
+}
+
+impl SchedulerInner {
+  const fn new() -> Self {
+    let mut threads = [const { Thread::new() }; NUM_THREADS];
+
+    threads[MAIN].name = "main";
+    threads[MAIN].state = State::Running;
+
+    Self {
+      threads,
+      current: MAIN,
+      need_resched: false,
+    }
+  }
+
+  /// Set up `slot` for a new thread running `entry`
+  fn start(&mut self, slot: usize, name: &'static str, entry: fn()) {
+    // Slot n uses stack n - 1; stacks grow down from their end
+    let stack_top = STACKS.get() as usize + slot * STACK_SIZE;
+
+    self.threads[slot] = Thread {
+      name,
+      state: State::Ready,
+      entry: Some(entry),
+      context: Context::new_thread(stack_top, thread_start),
+    };
+  }
+
+  fn wake_sleepers(&mut self, now: Duration) {
+    for t in self.threads.iter_mut() {
+      if let State::Sleeping(until) = t.state && until <= now { t.state = State::Ready; }
+    }
+  }
+
+  /// The first ready thread after the current one; the idle thread if there is none
+  fn pick_next(&self) -> usize {
+    (1..=NUM_THREADS).
+      map(|i| (self.current + i) % NUM_THREADS).
+      find(|&i| i != IDLE && self.threads[i].state == State::Ready).
+      unwrap_or(IDLE)
+  }
+
+  /// Make the next thread the current one; returns the contexts to switch between, `None` if it is the current one again
+  fn switch(&mut self) -> Option<(*mut Context, *const Context)> {
+    self.wake_sleepers(time::time_manager().uptime());
+    self.need_resched = false;
+
+    let prev = self.current;
+    let next = self.pick_next();
+
+    self.threads[next].state = State::Running;
+    self.current = next;
+
+    if next == prev { return None; }
+
+    Some((&raw mut self.threads[prev].context, &raw const self.threads[next].context))
+  }
+}
+
+/// Where new threads start; IRQs are still masked from the switch
+extern "C" fn thread_start() -> ! {
+  let entry = SCHEDULER.lock(|s| s.threads[s.current].entry);
+
+  local_irq_unmask();
+
+  if let Some(entry) = entry { entry(); }
+
+  exit()
+}
+
+fn idle() {
+  loop { cpu::wait_for_interrupt(); }
+}
+
+/// Put the running thread into `state` and continue the next one
+///
+/// Returns once the thread is continued; straight away if it is the next one itself
+fn switch_away(state: State) {
+  exec_with_irq_masked(|| {
+    let contexts = SCHEDULER.lock(|s| {
+      s.threads[s.current].state = state;
+      s.switch()
+    });
+
+    // The contexts stay in place in the static thread table; and nothing else switches while IRQs are masked
+    if let Some((prev, next)) = contexts {
+      unsafe { arch_thread::context_switch(prev, next) };
+    }
+  });
+}
+
+/// Switching away voluntarily is only fine without `NullLock`s held
+fn assert_preemptible() {
+  assert!(PREEMPT_COUNT.load(Ordering::Relaxed) == 0, "Thread switched away while holding a lock");
+}
+
+/// Set up the scheduler, start the timer tick and register the thread shell commands; the calling flow becomes the `main` thread
+pub fn init() -> Result<(), &'static str> {
+  if INITIALIZED.load(Ordering::Relaxed) { return Err("Already initialized"); }
+
+  SCHEDULER.lock(|s| s.start(IDLE, "idle", idle));
+  INITIALIZED.store(true, Ordering::Relaxed);
+
+  time::time_manager().start_tick();
+
+  shell::register_commands(&commands::COMMANDS)
+}
+
+/// Start a thread running `entry`; it exits once `entry` returns
+pub fn spawn(name: &'static str, entry: fn()) -> Result<(), &'static str> {
+  SCHEDULER.lock(|s| {
+    let slot = (IDLE + 1..NUM_THREADS).
+      find(|&i| s.threads[i].state == State::Free).
+      ok_or("No free thread slots")?;
+
+    s.start(slot, name, entry);
+
+    Ok(())
+  })
+}
+
+/// Let the other ready threads run first
+pub fn yield_now() {
+  if !INITIALIZED.load(Ordering::Relaxed) { return; }
+
+  assert_preemptible();
+  switch_away(State::Ready);
+}
+
+/// Let other threads run for at least `duration`; spins until `init`
+///
+/// Sleepers are woken by the timer tick; so the time is rounded up to the tick
+pub fn sleep(duration: Duration) {
+  if !INITIALIZED.load(Ordering::Relaxed) {
+    time::time_manager().spin_for(duration);
+    return;
+  }
+
+  assert_preemptible();
+  switch_away(State::Sleeping(time::time_manager().uptime() + duration));
+}
+
+/// End the running thread
+pub fn exit() -> ! {
+  assert_preemptible();
+  switch_away(State::Free);
+
+  unreachable!("Exited thread continued")
+}
+
+/// Called by the timer tick; the running thread's time slice is used up
+pub fn tick() {
+  SCHEDULER.lock(|s| s.need_resched = true);
+}
+
+/// Switch threads if the running one's time slice is used up; called on the way out of IRQ handling
+///
+/// The preempted thread continues here once it gets its turn again; and then returns from the exception
+pub fn preempt() {
+  if !INITIALIZED.load(Ordering::Relaxed) || PREEMPT_COUNT.load(Ordering::Relaxed) != 0 { return; }
+
+  if SCHEDULER.lock(|s| s.need_resched) { switch_away(State::Ready); }
+}
+
+/// Hold off preemption; until the matching `preempt_enable`
+pub fn preempt_disable() {
+  PREEMPT_COUNT.store(PREEMPT_COUNT.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+}
+
+/// Allow preemption again; once every `preempt_disable` is matched
+pub fn preempt_enable() {
+  PREEMPT_COUNT.store(PREEMPT_COUNT.load(Ordering::Relaxed) - 1, Ordering::Relaxed);
+}
+
+/// Call `f` with the name and the state of every thread
+pub fn for_each(mut f: impl FnMut(&'static str, State)) {
+  let mut threads = [("", State::Free); NUM_THREADS];
+
+  SCHEDULER.lock(|s| {
+    for (t, thread) in threads.iter_mut().zip(s.threads.iter()) { *t = (thread.name, thread.state); }
+  });
+
+  // Outside the lock; `f` may well print
+  threads.iter().filter(|(_, state)| *state != State::Free).for_each(|&(name, state)| f(name, state));
+}
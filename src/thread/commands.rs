@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//
+// Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+//! Thread shell commands
+
+use core::{
+  str::SplitWhitespace,
+  time::Duration,
+};
+
+use crate::{
+  println,
+  shell::{
+    parse_number,
+    Command,
+  },
+  thread,
+};
+
+pub const COMMANDS: [Command; 2] = [
+  Command::new("ps",       "List the kernel threads",                           ps),
+  Command::new("sleep",    "sleep <ms>: Let other threads run for a while",     sleep),
+];
+
+fn ps(_args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  thread::for_each(|name, state| println!("  {:<12} {}", name, state));
+
+  Ok(())
+}
+
+fn sleep(args: &mut SplitWhitespace) -> Result<(), &'static str> {
+  let ms = parse_number(args.next())?;
+
+  thread::sleep(Duration::from_millis(ms as u64));
+
+  Ok(())
+}
@@ -8,17 +8,44 @@
 #[path = "_arch/aarch64/time.rs"]
 mod arch_time;
 
-use core::time::Duration;
+use core::{
+  future::poll_fn,
+  task::{
+    Poll,
+    Waker,
+  },
+  time::Duration,
+};
+
+use crate::{
+  exception::asynchronous::interface::IRQHandler,
+  synchronization::{
+    interface::Mutex,
+    IRQSafeNullLock,
+  },
+  thread,
+};
+
+/// The interval of the timer tick; which is also the length of a thread's time slice
+pub const TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+const NUM_TIMEOUTS: usize = 16;
 
 pub struct TimeManager;
 
 static TIME_MANAGER: TimeManager = TimeManager::new();
 
+/// The wakers of sleeping async tasks with the uptime to wake them at; checked on every tick
+static TIMEOUTS: IRQSafeNullLock<[Option<(Duration, Waker)>; NUM_TIMEOUTS]> =
+  IRQSafeNullLock::new([const { None }; NUM_TIMEOUTS]);
+
 pub fn time_manager() -> &'static TimeManager {
   &TIME_MANAGER
 }
 
 impl TimeManager {
+  pub const COMPATIBLE: &'static str = "Architectural Timer";
+
   /// Create an instance
   pub const fn new() -> Self { Self }
 
@@ -37,4 +64,69 @@ impl TimeManager {
   pub fn spin_for(&self, duration: Duration) {
     arch_time::spin_for(duration);
   }
-}
\ No newline at end of file
+
+  /// Wait for a given duration without blocking; the async `spin_for`
+  ///
+  /// Timeouts are checked on the timer tick; so the time is rounded up to the tick
+  pub async fn sleep(&self, duration: Duration) {
+    let deadline = self.uptime() + duration;
+
+    poll_fn(|cx| {
+      if self.uptime() >= deadline { return Poll::Ready(()); }
+
+      add_timeout(deadline, cx.waker());
+
+      Poll::Pending
+    }).await
+  }
+
+  /// Start the timer tick; its IRQ must be registered and enabled
+  pub fn start_tick(&self) {
+    arch_time::set_timeout_irq(TICK_INTERVAL);
+  }
+}
+
+/// Have `waker` woken once the uptime reaches `deadline`; a task already waiting keeps the earlier of its two deadlines
+///
+/// Without a free slot the task is woken straight away; so it just polls again
+fn add_timeout(deadline: Duration, waker: &Waker) {
+  let added = TIMEOUTS.lock(|timeouts| {
+    let slot = timeouts.
+      iter().
+      position(|t| t.as_ref().is_some_and(|(_, w)| w.will_wake(waker))).
+      or_else(|| timeouts.iter().position(Option::is_none));
+
+    match slot.map(|slot| &mut timeouts[slot]) {
+      Some(Some((earlier, _))) => *earlier = (*earlier).min(deadline),
+      Some(free)               => *free = Some((deadline, waker.clone())),
+      None                     => (),
+    }
+
+    slot.is_some()
+  });
+
+  if !added { waker.wake_by_ref(); }
+}
+
+/// Wake the tasks whose timeouts have passed
+fn wake_expired(now: Duration) {
+  for slot in 0..NUM_TIMEOUTS {
+    let waker = TIMEOUTS.lock(|timeouts| match &timeouts[slot] {
+      Some((deadline, _)) if *deadline <= now => timeouts[slot].take().map(|(_, w)| w),
+      _                                       => None,
+    });
+
+    // Outside the lock; waking may take locks of its own
+    if let Some(waker) = waker { waker.wake(); }
+  }
+}
+
+impl IRQHandler for TimeManager {
+  fn handle(&self) -> Result<(), &'static str> {
+    arch_time::set_timeout_irq(TICK_INTERVAL);
+    wake_expired(self.uptime());
+    thread::tick();
+
+    Ok(())
+  }
+}
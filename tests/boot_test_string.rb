@@ -1,3 +1,3 @@
 # frozen_string_literal: true
 
-EXPECTED_STRING = 'lr : 0x'
\ No newline at end of file
+EXPECTED_PRINT = 'Kernel debug shell'
\ No newline at end of file
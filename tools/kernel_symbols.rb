@@ -0,0 +1,107 @@
+#!/usr/bin/env ruby
+# frozen_string_literal: true
+
+# SPDX-License-Identifier: MIT OR Apache-2.0
+#
+# Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+require 'rubygems'
+require 'bundler/setup'
+
+require 'colorize'
+require 'elftools'
+require 'open3'
+
+# Extracts the function symbols of the kernel ELF and patches them into its `.kernel_symbols` section.
+#
+# The generated table is what `crate::symbols` in the kernel parses. All values are little endian:
+#
+#   Header:       magic (u32), number of entries (u32), string table offset (u32), string table size (u32)
+#   Entries:      start address (u64), size (u32), name offset into the string table (u32)
+#                 Sorted by start address
+#   String table: Demangled names; each one NUL-terminated
+class KernelSymbols
+    SECTION_NAME = '.kernel_symbols'
+    MAGIC        = 0x4D59_534B # "KSYM"
+    HEADER_SIZE  = 16
+    ENTRY_SIZE   = 16
+    STT_FUNC     = 2
+
+    def initialize(kernel_elf_path)
+        @kernel_elf_path = kernel_elf_path
+    end
+
+    def run
+        symbols, section_offset, section_size = read_elf
+        table = build_table(symbols)
+
+        if table.bytesize > section_size
+            raise "Symbol table needs #{table.bytesize} bytes, but #{SECTION_NAME} only has #{section_size}. " \
+                  'Increase KERNEL_SYMBOLS_SIZE in kernel.ld'
+        end
+
+        File.open(@kernel_elf_path, 'r+b') do |f|
+            f.seek(section_offset)
+            f.write(table)
+        end
+
+        puts format('%<prefix>12s %<count>d symbols, %<used>d of %<size>d bytes',
+                    prefix: 'Symbols'.green.bold, count: symbols.length, used: table.bytesize, size: section_size)
+    end
+
+    private
+
+    def read_elf
+        File.open(@kernel_elf_path, 'rb') do |f|
+            elf = ELFTools::ELFFile.new(f)
+
+            section = elf.section_by_name(SECTION_NAME)
+            raise "#{SECTION_NAME} section not found in #{@kernel_elf_path}" if section.nil?
+
+            symbols = elf.section_by_name('.symtab').symbols.select do |s|
+                (s.header.st_info & 0xf) == STT_FUNC && s.header.st_size.positive?
+            end
+
+            symbols = symbols.map { |s| [s.header.st_value, s.header.st_size, s.name] }
+
+            [symbols, section.header.sh_offset, section.header.sh_size]
+        end
+    end
+
+    # Demangle all names in one go; fall back to the mangled names if rustfilt isn't installed
+    def demangle(names)
+        out, status = Open3.capture2('rustfilt', stdin_data: names.join("\n"))
+        return names unless status.success?
+
+        out.split("\n")
+    rescue Errno::ENOENT
+        warn 'rustfilt not found; kernel symbols will not be demangled'.yellow
+        names
+    end
+
+    def build_table(symbols)
+        # Aliases share a start address; keep only the first one
+        symbols = symbols.sort_by(&:first).uniq(&:first)
+        names = demangle(symbols.map(&:last))
+
+        entries = String.new(encoding: Encoding::BINARY)
+        strings = String.new(encoding: Encoding::BINARY)
+
+        symbols.zip(names).each do |(start, size, _), name|
+            entries << [start, size, strings.bytesize].pack('Q<L<L<')
+            strings << name.b << "\0"
+        end
+
+        strtab_offset = HEADER_SIZE + (symbols.length * ENTRY_SIZE)
+        header = [MAGIC, symbols.length, strtab_offset, strings.bytesize].pack('L<4')
+
+        header + entries + strings
+    end
+end
+
+if ARGV.length != 1
+    puts "Usage: #{$PROGRAM_NAME} <kernel ELF>"
+    exit 1
+end
+
+KernelSymbols.new(ARGV[0]).run
@@ -0,0 +1,74 @@
+#!/usr/bin/env ruby
+# frozen_string_literal: true
+
+# SPDX-License-Identifier: MIT OR Apache-2.0
+#
+# Copyright (c) 2025 Bryan Maynard <bsgbryan@gmail.com>
+
+file_dir = File.dirname(__FILE__)
+$LOAD_PATH.unshift(file_dir) unless $LOAD_PATH.include?(file_dir)
+
+require 'fileutils'
+require 'socket'
+require 'boot_test'
+
+# Dump QEMU's display through the monitor and check that the framebuffer console drew on it.
+class ScreendumpSubtest < SubtestBase
+    DISPLAY_SIZE = [640, 480].freeze
+
+    def initialize(monitor_socket, dump_file)
+        super()
+        @monitor_socket = monitor_socket
+        @dump_file = dump_file
+    end
+
+    def name
+        'Checking a screendump for the framebuffer console'
+    end
+
+    def run(_qemu_out, _qemu_in)
+        FileUtils.rm_f(@dump_file)
+
+        UNIXSocket.open(@monitor_socket) do |monitor|
+            expect_or_raise(monitor, '(qemu)')
+            monitor.puts("screendump #{@dump_file}")
+            expect_or_raise(monitor, '(qemu)')
+        end
+
+        width, height, pixels = read_ppm(@dump_file)
+
+        raise "Unexpected display size: #{width}x#{height}" unless [width, height] == DISPLAY_SIZE
+        # Text on the background takes at least two colors
+        raise 'The display is blank' if pixels.unpack('C*').each_slice(3).uniq.length < 2
+    ensure
+        FileUtils.rm_f(@dump_file)
+    end
+
+    private
+
+    # The width, height and raw RGB pixels of a binary PPM.
+    def read_ppm(file)
+        data = File.binread(file)
+        header = /\AP6\s+(\d+)\s+(\d+)\s+255\s/.match(data)
+
+        raise "#{file} is not a binary PPM" if header.nil?
+
+        [header[1].to_i, header[2].to_i, data[header.end(0)..]]
+    end
+end
+
+# Boot the kernel in QEMU and check its display once the shell is up.
+class ScreendumpTest < ConsoleIOTest
+    MONITOR_SOCKET = 'qemu-monitor.sock'
+    DUMP_FILE = 'screendump.ppm'
+
+    def initialize(qemu_cmd, expected_print)
+        subtests = [ExpectedBootPrintTest.new(expected_print), ScreendumpSubtest.new(MONITOR_SOCKET, DUMP_FILE)]
+        qemu_cmd = "#{qemu_cmd} -monitor unix:#{MONITOR_SOCKET},server=on,wait=off"
+
+        super(qemu_cmd, 'Screendump test', subtests)
+    end
+end
+
+load 'tests/boot_test_string.rb' # provides 'EXPECTED_PRINT'
+ScreendumpTest.new(ARGV.join(' '), EXPECTED_PRINT).run # Doesn't return